@@ -0,0 +1,337 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use duckdb::{
+    ToSql,
+    types::{ToSqlOutput, Value as DuckDbValue},
+};
+
+use crate::{Column, ExportFormat, Sink, Value};
+
+// 38 is the widest precision duckdb supports. 28 decimal places keep every digit a rust_decimal
+// can hold after the point and leave 10 digits for the integer part, larger values fail to insert.
+const DECIMAL: &str = "DECIMAL(38, 28)";
+
+pub struct DuckDbSink {
+    pub(crate) connection: duckdb::Connection,
+}
+
+impl DuckDbSink {
+    pub fn connect(file: &str) -> anyhow::Result<Self> {
+        let connection = duckdb::Connection::open(file)?;
+        connection.execute("SET TimeZone = 'UTC'", [])?;
+
+        Ok(Self { connection })
+    }
+
+    // Only databases that are exported to get the exports table.
+    fn create_exports_table(&self) -> anyhow::Result<()> {
+        self.connection.execute(
+            r#"
+                CREATE TABLE IF NOT EXISTS exports (
+                    table_name TEXT NOT NULL,
+                    destination TEXT NOT NULL,
+                    processed_until timestamptz NOT NULL,
+                    exported_at timestamptz NOT NULL
+                )
+            "#,
+            [],
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Sink for DuckDbSink {
+    fn execute_batch(&self, sql: &str) -> anyhow::Result<()> {
+        self.connection.execute_batch(sql)?;
+
+        Ok(())
+    }
+
+    fn table_columns(&self, table: &str) -> anyhow::Result<Vec<Column>> {
+        self.connection
+            .prepare(
+                r#"
+                    SELECT column_name, data_type, is_nullable = 'YES'
+                    FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name = ?
+                    ORDER BY ordinal_position
+                "#,
+            )?
+            .query_map([table], |r| {
+                Ok(Column {
+                    name: r.get(0)?,
+                    data_type: r.get(1)?,
+                    nullable: r.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(anyhow::Error::from)
+    }
+
+    fn primary_key(&self, table: &str) -> anyhow::Result<Vec<String>> {
+        self.connection
+            .prepare(
+                r#"
+                    SELECT kcu.column_name
+                    FROM information_schema.table_constraints tc
+                    JOIN information_schema.key_column_usage kcu
+                        ON kcu.constraint_name = tc.constraint_name
+                        AND kcu.table_schema = tc.table_schema
+                        AND kcu.table_name = tc.table_name
+                    WHERE tc.table_schema = current_schema()
+                        AND tc.table_name = ?
+                        AND tc.constraint_type = 'PRIMARY KEY'
+                    ORDER BY kcu.ordinal_position
+                "#,
+            )?
+            .query_map([table], |r| r.get(0))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(anyhow::Error::from)
+    }
+
+    fn column_type(&self, sql_type: &str) -> String {
+        if sql_type.eq_ignore_ascii_case("decimal") {
+            DECIMAL.to_string()
+        } else {
+            sql_type.to_string()
+        }
+    }
+
+    fn same_type(&self, existing: &str, sql_type: &str) -> bool {
+        canonical_type(existing) == canonical_type(sql_type)
+    }
+
+    fn can_convert(&self, existing: &str, sql_type: &str) -> bool {
+        let sql_type = canonical_type(sql_type);
+        match canonical_type(existing).as_str() {
+            "DOUBLE" => sql_type.starts_with("DECIMAL"),
+            "VARCHAR" => sql_type == "JSON" || sql_type == "TIMESTAMP WITH TIME ZONE",
+            existing => adds_decimal_places(existing, &sql_type),
+        }
+    }
+
+    // DuckDB cannot alter tables that have an index, which includes primary keys.
+    fn can_add_columns(&self, has_primary_key: bool) -> bool {
+        !has_primary_key
+    }
+
+    fn save_file_processed(
+        &self,
+        name: &str,
+        prefix: &str,
+        timestamp: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        self.connection.execute("INSERT INTO files_processed(file_name, prefix, file_timestamp, processed_at) VALUES(?, ?, ? ,?)", duckdb::params![name, prefix, timestamp, Utc::now()])?;
+
+        Ok(())
+    }
+
+    fn latest_file_processed_timestamp(&self, prefix: &str) -> anyhow::Result<DateTime<Utc>> {
+        self.connection
+            .prepare(
+                r#"
+                    SELECT file_timestamp
+                    FROM files_processed
+                    WHERE prefix = ?
+                    ORDER BY file_timestamp DESC
+                    LIMIT 1
+                "#,
+            )?
+            .query_row([prefix], |r| r.get(0))
+            .map_err(anyhow::Error::from)
+    }
+
+    fn latest_schema_version(&self, table: &str) -> anyhow::Result<Option<i32>> {
+        self.connection
+            .prepare("SELECT MAX(version) FROM schema_versions WHERE table_name = ?")?
+            .query_row([table], |r| r.get(0))
+            .map_err(anyhow::Error::from)
+    }
+
+    fn save_schema_version(&self, table: &str, columns: &str) -> anyhow::Result<()> {
+        self.connection.execute(
+            r#"
+                INSERT INTO schema_versions(table_name, version, columns, applied_at)
+                SELECT ?, COALESCE(MAX(version), 0) + 1, ?, ?
+                FROM schema_versions
+                WHERE table_name = ?
+            "#,
+            duckdb::params![table, columns, Utc::now(), table],
+        )?;
+
+        Ok(())
+    }
+
+    fn append(&self, table: &str, rows: Vec<Vec<Value>>) -> anyhow::Result<()> {
+        let mut appender = self.connection.appender(table)?;
+        for row in rows {
+            appender.append_row(duckdb::appender_params_from_iter(row))?;
+        }
+
+        Ok(())
+    }
+
+    // Exports rows from files processed since the last export of this table to the same
+    // destination, partitioned by day of `partition_column` or of the file timestamp.
+    fn export_table(
+        &self,
+        table: &str,
+        format: ExportFormat,
+        output: &Path,
+        partition_column: Option<&str>,
+    ) -> anyhow::Result<usize> {
+        let columns = self.table_columns(table)?;
+        if columns.is_empty() {
+            anyhow::bail!("table {table} does not exist");
+        }
+        if !columns.iter().any(|c| c.name == "file_source") {
+            anyhow::bail!("table {table} has no file_source column, it cannot be exported");
+        }
+
+        let partition = match partition_column {
+            Some(column) if columns.iter().any(|c| c.name == column) => {
+                format!("t.{}", quote_identifier(column))
+            }
+            Some(column) => {
+                tracing::warn!(
+                    table,
+                    column,
+                    "partition column not found, partitioning by file timestamp"
+                );
+                "f.file_timestamp".to_string()
+            }
+            None => "f.file_timestamp".to_string(),
+        };
+
+        let destination = output.join(table).to_string_lossy().to_string();
+        let table_sql = quote_identifier(table);
+        let destination_sql = quote_literal(&destination);
+
+        self.create_exports_table()?;
+
+        let last_export: Option<DateTime<Utc>> = self
+            .connection
+            .prepare(
+                r#"
+                    SELECT MAX(processed_until)
+                    FROM exports
+                    WHERE table_name = ? AND destination = ?
+                "#,
+            )?
+            .query_row([table, destination.as_str()], |r| r.get(0))?;
+
+        let processed_until: Option<DateTime<Utc>> = self
+            .connection
+            .prepare("SELECT MAX(processed_at) FROM files_processed")?
+            .query_row([], |r| r.get(0))?;
+
+        let Some(processed_until) = processed_until else {
+            return Ok(0);
+        };
+
+        if last_export.is_some_and(|last| last >= processed_until) {
+            return Ok(0);
+        }
+
+        let after = last_export.unwrap_or(DateTime::<Utc>::UNIX_EPOCH);
+
+        let rows = self.connection.execute(
+            &format!(
+                r#"
+                    COPY (
+                        SELECT t.*, strftime({partition}, '%Y-%m-%d') AS day
+                        FROM {table_sql} t
+                        JOIN (
+                            SELECT file_name, MAX(file_timestamp) AS file_timestamp
+                            FROM files_processed
+                            GROUP BY file_name
+                        ) f ON f.file_name = t.file_source
+                        WHERE t.file_source IN (
+                            SELECT file_name
+                            FROM files_processed
+                            WHERE processed_at > '{}' AND processed_at <= '{}'
+                        )
+                    ) TO {destination_sql} (FORMAT {}, PARTITION_BY (day), APPEND)
+                "#,
+                after.to_rfc3339(),
+                processed_until.to_rfc3339(),
+                format.as_str(),
+            ),
+            [],
+        )?;
+
+        self.connection.execute(
+            "INSERT INTO exports(table_name, destination, processed_until, exported_at) VALUES(?, ?, ?, ?)",
+            duckdb::params![table, destination, processed_until, Utc::now()],
+        )?;
+
+        Ok(rows)
+    }
+}
+
+impl ToSql for Value {
+    fn to_sql(&self) -> duckdb::Result<ToSqlOutput<'_>> {
+        match self {
+            Value::Null => Ok(ToSqlOutput::Owned(DuckDbValue::Null)),
+            Value::Bool(v) => v.to_sql(),
+            Value::Int32(v) => v.to_sql(),
+            Value::UInt32(v) => v.to_sql(),
+            Value::Int64(v) => v.to_sql(),
+            Value::UInt64(v) => v.to_sql(),
+            Value::Double(v) => v.to_sql(),
+            // passed as text and cast by duckdb to the column's precision and scale
+            Value::Decimal(v) => Ok(ToSqlOutput::Owned(DuckDbValue::Text(v.to_string()))),
+            Value::Text(v) => v.to_sql(),
+            Value::Json(v) => v.to_sql(),
+            Value::Timestamp(v) => v.to_sql(),
+            Value::Blob(v) => v.to_sql(),
+        }
+    }
+}
+
+// COPY does not accept parameters, so names and the destination are quoted into the statement.
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+// Decimals can be converted to a type with more decimal places, such as the DECIMAL(38,18) that
+// DECIMAL used to map to. Values whose integer part no longer fits fail the cast.
+fn adds_decimal_places(existing: &str, sql_type: &str) -> bool {
+    match (decimal_digits(existing), decimal_digits(sql_type)) {
+        (Some((_, scale)), Some((_, new_scale))) => new_scale > scale,
+        _ => false,
+    }
+}
+
+fn decimal_digits(sql_type: &str) -> Option<(u32, u32)> {
+    let (precision, scale) = sql_type
+        .strip_prefix("DECIMAL(")?
+        .strip_suffix(')')?
+        .split_once(',')?;
+
+    Some((precision.parse().ok()?, scale.parse().ok()?))
+}
+
+// Maps the type aliases accepted by DuckDB to the names reported by information_schema.
+fn canonical_type(sql_type: &str) -> String {
+    let sql_type = sql_type.trim().to_uppercase();
+    match sql_type.as_str() {
+        "TEXT" | "STRING" | "VARCHAR" => "VARCHAR".to_string(),
+        "UINT64" | "UBIGINT" => "UBIGINT".to_string(),
+        "UINT32" | "UINTEGER" => "UINTEGER".to_string(),
+        "INT64" | "INT8" | "LONG" | "BIGINT" => "BIGINT".to_string(),
+        "INT32" | "INT4" | "INT" | "SIGNED" | "INTEGER" => "INTEGER".to_string(),
+        "FLOAT8" | "DOUBLE" => "DOUBLE".to_string(),
+        "BOOL" | "LOGICAL" | "BOOLEAN" => "BOOLEAN".to_string(),
+        "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE" => "TIMESTAMP WITH TIME ZONE".to_string(),
+        "BYTEA" | "BINARY" | "VARBINARY" | "BLOB" => "BLOB".to_string(),
+        "DECIMAL" | "NUMERIC" => DECIMAL.replace(' ', ""),
+        _ => sql_type.replace(' ', ""),
+    }
+}
@@ -1,20 +1,39 @@
+mod duckdb_sink;
+mod postgres_sink;
+
+use std::path::Path;
+
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+pub use duckdb_sink::DuckDbSink;
+pub use postgres_sink::PostgresSink;
 
 pub struct Db {
-    connection: duckdb::Connection,
+    sink: Box<dyn Sink>,
 }
 
 impl Db {
-    pub fn connect(file: &str) -> anyhow::Result<Self> {
-        let connection = duckdb::Connection::open(file)?;
-        connection.execute("SET TimeZone = 'UTC'", [])?;
-        Self::create_files_processed_table(&connection)?;
+    pub fn connect(url: &str) -> anyhow::Result<Self> {
+        let sink: Box<dyn Sink> =
+            if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+                Box::new(PostgresSink::connect(url)?)
+            } else {
+                Box::new(DuckDbSink::connect(url)?)
+            };
 
-        Ok(Self { connection })
+        Self::new(sink)
     }
 
-    fn create_files_processed_table(connection: &duckdb::Connection) -> anyhow::Result<()> {
-        connection.execute(
+    fn new(sink: Box<dyn Sink>) -> anyhow::Result<Self> {
+        Self::create_files_processed_table(sink.as_ref())?;
+        Self::create_schema_versions_table(sink.as_ref())?;
+
+        Ok(Self { sink })
+    }
+
+    fn create_files_processed_table(sink: &dyn Sink) -> anyhow::Result<()> {
+        sink.execute_batch(
             r#"
                 CREATE TABLE IF NOT EXISTS files_processed (
                     file_name TEXT NOT NULL,
@@ -23,10 +42,20 @@ impl Db {
                     processed_at timestamptz NOT NULL
                 )
             "#,
-            [],
-        )?;
+        )
+    }
 
-        Ok(())
+    fn create_schema_versions_table(sink: &dyn Sink) -> anyhow::Result<()> {
+        sink.execute_batch(
+            r#"
+                CREATE TABLE IF NOT EXISTS schema_versions (
+                    table_name TEXT NOT NULL,
+                    version INTEGER NOT NULL,
+                    columns TEXT NOT NULL,
+                    applied_at timestamptz NOT NULL
+                )
+            "#,
+        )
     }
 
     pub fn save_file_processed(
@@ -35,86 +64,933 @@ impl Db {
         prefix: &str,
         timestamp: DateTime<Utc>,
     ) -> anyhow::Result<()> {
-        self.connection.execute("INSERT INTO files_processed(file_name, prefix, file_timestamp, processed_at) VALUES(?, ?, ? ,?)", duckdb::params![name, prefix, timestamp, Utc::now()])?;
-
-        Ok(())
+        self.sink.save_file_processed(name, prefix, timestamp)
     }
 
     pub fn latest_file_processed_timestamp(&self, prefix: &str) -> anyhow::Result<DateTime<Utc>> {
-        self.connection
-            .prepare(
-                r#"
-                    SELECT file_timestamp
-                    FROM files_processed
-                    WHERE prefix = ?
-                    ORDER BY file_timestamp DESC
-                    LIMIT 1
-                "#,
-            )?
-            .query_row([prefix], |r| r.get(0))
-            .map_err(anyhow::Error::from)
+        self.sink.latest_file_processed_timestamp(prefix)
     }
 
     pub fn create_table(&self, name: &str, fields: Vec<TableField>) -> anyhow::Result<()> {
-        let statement = format!(
-            "CREATE TABLE IF NOT EXISTS {} ({})",
-            name,
-            fields
+        let existing = self.sink.table_columns(name)?;
+
+        if existing.is_empty() {
+            self.sink
+                .execute_batch(&self.create_table_statement(name, &fields, &[]))?;
+            self.save_schema_version(name, &fields)?;
+        } else {
+            self.migrate_table(name, &fields, &existing)?;
+        }
+
+        Ok(())
+    }
+
+    // Existing tables are brought up to date by adding any new columns as nullable. Columns added
+    // after the existing ones are added in place. Any other change rebuilds the table so that
+    // column order keeps matching the appender, and so that primary keys can be added, in which
+    // case existing duplicate rows are dropped. Columns are only converted to another type where
+    // the sink allows it, such as DOUBLE to DECIMAL or TEXT to JSON, and NOT NULL is dropped from
+    // columns that have become optional.
+    fn migrate_table(
+        &self,
+        name: &str,
+        fields: &[TableField],
+        existing: &[Column],
+    ) -> anyhow::Result<()> {
+        for Column { name: column, .. } in existing {
+            if !fields.iter().any(|f| &f.name == column) {
+                anyhow::bail!(
+                    "table {name} has column {column} which is no longer defined, refusing to drop it"
+                );
+            }
+        }
+
+        let mut added = Vec::new();
+        let mut converted = Vec::new();
+        let mut relaxed = Vec::new();
+        for field in fields {
+            let Some(column) = existing.iter().find(|c| c.name == field.name) else {
+                added.push(field.name.as_str());
+                continue;
+            };
+
+            if self.sink.can_convert(&column.data_type, field.sql_type()) {
+                converted.push(field.name.as_str());
+            } else if !self.sink.same_type(&column.data_type, field.sql_type()) {
+                anyhow::bail!(
+                    "table {name} column {} has type {} but {} is required, migrate or drop the table manually",
+                    field.name,
+                    column.data_type,
+                    self.sink.column_type(field.sql_type())
+                );
+            }
+
+            if !column.nullable && field.is_nullable() {
+                relaxed.push(field.name.as_str());
+            }
+        }
+
+        let keys = primary_key(fields);
+        if let Some(column) = keys.iter().find(|k| added.contains(*k)) {
+            anyhow::bail!(
+                "table {name} is missing key column {column}, it cannot be added to an existing table"
+            );
+        }
+
+        let same_order = existing
+            .iter()
+            .map(|c| c.name.as_str())
+            .eq(fields.iter().map(|f| f.name.as_str()));
+
+        let existing_key = self.sink.primary_key(name)?;
+        let same_key = existing_key == keys;
+
+        if same_order && same_key && converted.is_empty() && relaxed.is_empty() {
+            if self.sink.latest_schema_version(name)?.is_none() {
+                self.save_schema_version(name, fields)?;
+            }
+            return Ok(());
+        }
+
+        let appended = existing
+            .iter()
+            .map(|c| c.name.as_str())
+            .eq(fields[..existing.len()].iter().map(|f| f.name.as_str()));
+
+        if appended
+            && same_key
+            && converted.is_empty()
+            && relaxed.is_empty()
+            && self.sink.can_add_columns(!existing_key.is_empty())
+        {
+            tracing::info!(table = name, columns = ?added, "adding columns");
+
+            let statements = fields[existing.len()..]
                 .iter()
-                .map(|f| f.to_sql())
-                .collect::<Vec<_>>()
-                .join(","),
+                .map(|f| {
+                    format!(
+                        "ALTER TABLE {name} ADD COLUMN {} {};",
+                        f.name,
+                        self.sink.column_type(f.sql_type())
+                    )
+                })
+                .collect::<String>();
+
+            return self.transaction(|| {
+                self.sink.execute_batch(&statements)?;
+                self.save_schema_version(name, fields)
+            });
+        }
+
+        tracing::info!(
+            table = name,
+            columns = ?added,
+            converted = ?converted,
+            nullable = ?relaxed,
+            key = ?keys,
+            "migrating table"
         );
 
-        self.connection.execute(&statement, [])?;
+        let temp = format!("{name}__migration");
+        let columns = existing
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        let values = existing
+            .iter()
+            .map(|c| match fields.iter().find(|f| f.name == c.name) {
+                Some(f) if converted.contains(&c.name.as_str()) => {
+                    format!(
+                        "CAST({} AS {})",
+                        c.name,
+                        self.sink.column_type(f.sql_type())
+                    )
+                }
+                _ => c.name.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(",");
 
-        Ok(())
+        // Columns added by earlier migrations hold NULL for older rows, so they stay nullable.
+        let nullable = existing
+            .iter()
+            .filter(|c| c.nullable)
+            .map(|c| c.name.as_str())
+            .chain(added.iter().copied())
+            .collect::<Vec<_>>();
+
+        let on_conflict = if keys.is_empty() {
+            ""
+        } else {
+            " ON CONFLICT DO NOTHING"
+        };
+
+        self.transaction(|| {
+            self.sink.execute_batch(&format!(
+                "{}; INSERT INTO {temp} ({columns}) SELECT {values} FROM {name}{on_conflict}; DROP TABLE {name}; ALTER TABLE {temp} RENAME TO {name};",
+                self.create_table_statement(&temp, fields, &nullable),
+            ))?;
+            self.save_schema_version(name, fields)
+        })
+    }
+
+    fn transaction(&self, f: impl FnOnce() -> anyhow::Result<()>) -> anyhow::Result<()> {
+        self.sink.execute_batch("BEGIN")?;
+
+        match f() {
+            Ok(()) => self.sink.execute_batch("COMMIT"),
+            Err(err) => {
+                self.sink.execute_batch("ROLLBACK")?;
+                Err(err)
+            }
+        }
+    }
+
+    fn save_schema_version(&self, name: &str, fields: &[TableField]) -> anyhow::Result<()> {
+        let columns = fields
+            .iter()
+            .map(|f| format!("{} {}", f.name, f.sql_type()))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        self.sink.save_schema_version(name, &columns)
+    }
+
+    fn create_table_statement(
+        &self,
+        name: &str,
+        fields: &[TableField],
+        nullable: &[&str],
+    ) -> String {
+        let mut columns = fields
+            .iter()
+            .map(|f| {
+                let nullable = if nullable.contains(&f.name.as_str()) || f.is_nullable() {
+                    "NULL"
+                } else {
+                    "NOT NULL"
+                };
+
+                format!(
+                    "{} {} {}",
+                    f.name,
+                    self.sink.column_type(f.sql_type()),
+                    nullable
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let keys = primary_key(fields);
+        if !keys.is_empty() {
+            columns.push(format!("PRIMARY KEY ({})", keys.join(",")));
+        }
+
+        format!(
+            "CREATE TABLE IF NOT EXISTS {} ({})",
+            name,
+            columns.join(",")
+        )
+    }
+
+    pub fn export_table(
+        &self,
+        table: &str,
+        format: ExportFormat,
+        output: &Path,
+        partition_column: Option<&str>,
+    ) -> anyhow::Result<usize> {
+        self.sink
+            .export_table(table, format, output, partition_column)
     }
 
     pub fn append_to_table<A>(&self, table: &str, data: Vec<A>) -> anyhow::Result<()>
     where
         A: Appendable,
     {
-        let mut appender = self.connection.appender(table)?;
-        for entry in data {
-            entry.append(&mut appender)?;
+        let rows = data.iter().map(Appendable::row).collect();
+        self.sink.append(table, rows)
+    }
+
+    // Child tables start with the parent's foreign key column, followed by the child's own columns.
+    // The table is named by the parent, so one child type can be stored under several parents.
+    pub fn create_child_table<T>(&self, table: &str, foreign_key: TableField) -> anyhow::Result<()>
+    where
+        T: Table,
+    {
+        let mut fields = vec![foreign_key];
+        fields.extend(T::fields());
+        self.create_table(table, fields)
+    }
+
+    pub fn append_children<T>(&self, table: &str, children: Vec<(Value, T)>) -> anyhow::Result<()>
+    where
+        T: Table,
+    {
+        let rows = children
+            .iter()
+            .map(|(key, child)| {
+                let mut row = vec![key.clone()];
+                row.extend(child.row());
+                row
+            })
+            .collect();
+        self.sink.append(table, rows)
+    }
+
+    // Rows are appended to a staging table first and then merged, so that rows already imported
+    // from overlapping files are skipped or replaced based on the primary key. When a key repeats
+    // within the batch, the first row is kept for Ignore and the last one for Replace, matching
+    // what importing the rows one at a time would do.
+    pub fn upsert_to_table<A>(
+        &self,
+        table: &str,
+        keys: &[&str],
+        on_conflict: OnConflict,
+        data: Vec<A>,
+    ) -> anyhow::Result<()>
+    where
+        A: Appendable,
+    {
+        if data.is_empty() {
+            return Ok(());
         }
 
-        Ok(())
+        let staging = format!("{table}__staging");
+        let columns = self
+            .sink
+            .table_columns(table)?
+            .into_iter()
+            .map(|c| c.name)
+            .collect::<Vec<_>>();
+
+        let (order, action) = match on_conflict {
+            OnConflict::Ignore => ("ASC", "DO NOTHING".to_string()),
+            OnConflict::Replace => {
+                let updates = columns
+                    .iter()
+                    .filter(|c| !keys.contains(&c.as_str()))
+                    .map(|c| format!("{c} = EXCLUDED.{c}"))
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                ("DESC", format!("DO UPDATE SET {updates}"))
+            }
+        };
+
+        // the same key can appear more than once within a batch, which ON CONFLICT DO UPDATE
+        // does not allow, so staging_row records the batch order to pick one row per key
+        let keys = keys.join(",");
+        let columns = columns.join(",");
+        let conflict = format!(
+            "SELECT DISTINCT ON ({keys}) {columns} FROM {staging} ORDER BY {keys}, staging_row {order} ON CONFLICT ({keys}) {action}"
+        );
+
+        let rows = data
+            .iter()
+            .enumerate()
+            .map(|(i, data)| {
+                let mut row = data.row();
+                row.push(Value::Int64(i as i64));
+                row
+            })
+            .collect();
+
+        self.transaction(|| {
+            self.sink.execute_batch(&format!(
+                "DROP TABLE IF EXISTS {staging}; CREATE TABLE {staging} AS SELECT *, CAST(0 AS BIGINT) AS staging_row FROM {table} LIMIT 0;"
+            ))?;
+            self.sink.append(&staging, rows)?;
+            self.sink.execute_batch(&format!(
+                "INSERT INTO {table} ({columns}) {conflict}; DROP TABLE {staging};"
+            ))
+        })
+    }
+}
+
+fn primary_key(fields: &[TableField]) -> Vec<&str> {
+    fields
+        .iter()
+        .filter(|f| f.key)
+        .map(|f| f.name.as_str())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum OnConflict {
+    Ignore,
+    Replace,
+}
+
+pub trait Sink {
+    fn execute_batch(&self, sql: &str) -> anyhow::Result<()>;
+
+    fn table_columns(&self, table: &str) -> anyhow::Result<Vec<Column>>;
+
+    fn primary_key(&self, table: &str) -> anyhow::Result<Vec<String>>;
+
+    // Translates a `TableField` SQL type to the type used in this database's DDL.
+    fn column_type(&self, sql_type: &str) -> String;
+
+    // Compares a type reported by information_schema with a `TableField` SQL type.
+    fn same_type(&self, existing: &str, sql_type: &str) -> bool;
+
+    // Whether a column of the existing type can be rebuilt as a `TableField` SQL type.
+    fn can_convert(&self, _existing: &str, _sql_type: &str) -> bool {
+        false
+    }
+
+    // Whether columns can be added to an existing table in place.
+    fn can_add_columns(&self, _has_primary_key: bool) -> bool {
+        true
+    }
+
+    fn save_file_processed(
+        &self,
+        name: &str,
+        prefix: &str,
+        timestamp: DateTime<Utc>,
+    ) -> anyhow::Result<()>;
+
+    fn latest_file_processed_timestamp(&self, prefix: &str) -> anyhow::Result<DateTime<Utc>>;
+
+    fn latest_schema_version(&self, table: &str) -> anyhow::Result<Option<i32>>;
+
+    fn save_schema_version(&self, table: &str, columns: &str) -> anyhow::Result<()>;
+
+    fn append(&self, table: &str, rows: Vec<Vec<Value>>) -> anyhow::Result<()>;
+
+    fn export_table(
+        &self,
+        _table: &str,
+        _format: ExportFormat,
+        _output: &Path,
+        _partition_column: Option<&str>,
+    ) -> anyhow::Result<usize> {
+        anyhow::bail!("export is only supported for DuckDB databases")
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExportFormat {
+    Parquet,
+    Csv,
+}
+
+impl ExportFormat {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::Csv => "csv",
+        }
     }
 }
 
 pub trait Appendable {
-    fn append(&self, appender: &mut duckdb::Appender) -> anyhow::Result<()>;
+    fn row(&self) -> Vec<Value>;
+}
+
+pub trait Table: Appendable {
+    const NAME: &'static str;
+
+    const COLUMNS: &'static [&'static str];
+
+    fn fields() -> Vec<TableField>;
+}
+
+// Used by the Import derive to check at compile time that a child's foreign key column does not
+// clash with one of the child's own columns.
+pub const fn has_column(columns: &[&str], name: &str) -> bool {
+    let mut i = 0;
+    while i < columns.len() {
+        if columns[i].as_bytes().eq_ignore_ascii_case(name.as_bytes()) {
+            return true;
+        }
+        i += 1;
+    }
+
+    false
+}
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int32(i32),
+    UInt32(u32),
+    Int64(i64),
+    UInt64(u64),
+    Double(f64),
+    Decimal(Decimal),
+    Text(String),
+    Json(serde_json::Value),
+    Timestamp(DateTime<Utc>),
+    Blob(Vec<u8>),
+}
+
+pub trait ToValue {
+    fn to_value(&self) -> Value;
+}
+
+impl ToValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl ToValue for i32 {
+    fn to_value(&self) -> Value {
+        Value::Int32(*self)
+    }
+}
+
+impl ToValue for u32 {
+    fn to_value(&self) -> Value {
+        Value::UInt32(*self)
+    }
+}
+
+impl ToValue for i64 {
+    fn to_value(&self) -> Value {
+        Value::Int64(*self)
+    }
+}
+
+impl ToValue for u64 {
+    fn to_value(&self) -> Value {
+        Value::UInt64(*self)
+    }
+}
+
+impl ToValue for f64 {
+    fn to_value(&self) -> Value {
+        Value::Double(*self)
+    }
+}
+
+impl ToValue for Decimal {
+    fn to_value(&self) -> Value {
+        Value::Decimal(*self)
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self) -> Value {
+        Value::Text(self.clone())
+    }
+}
+
+impl ToValue for serde_json::Value {
+    fn to_value(&self) -> Value {
+        Value::Json(self.clone())
+    }
+}
+
+impl ToValue for DateTime<Utc> {
+    fn to_value(&self) -> Value {
+        Value::Timestamp(*self)
+    }
+}
+
+impl ToValue for Vec<u8> {
+    fn to_value(&self) -> Value {
+        Value::Blob(self.clone())
+    }
+}
+
+impl<T: ToValue> ToValue for Option<T> {
+    fn to_value(&self) -> Value {
+        self.as_ref().map_or(Value::Null, ToValue::to_value)
+    }
+}
+
+// A column as reported by information_schema.
+pub struct Column {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
 }
 
 pub struct TableField {
     name: String,
     sql_type: Option<String>,
     nullable: Option<bool>,
+    key: bool,
 }
 
 impl TableField {
-    pub fn new(name: String, sql_type: Option<String>, nullable: Option<bool>) -> Self {
+    pub fn new(name: String, sql_type: Option<String>, nullable: Option<bool>, key: bool) -> Self {
         Self {
             name,
             sql_type,
             nullable,
+            key,
         }
     }
 
-    fn to_sql(&self) -> String {
-        let nullable = if self.nullable.unwrap_or(false) {
-            "NULL"
-        } else {
-            "NOT NULL"
-        };
+    fn sql_type(&self) -> &str {
+        self.sql_type.as_deref().unwrap_or("TEXT")
+    }
 
-        format!(
-            "{} {} {}",
-            self.name,
-            self.sql_type.as_deref().unwrap_or("TEXT"),
-            nullable
+    fn is_nullable(&self) -> bool {
+        self.nullable.unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    struct Row(Vec<Value>);
+
+    impl Appendable for Row {
+        fn row(&self) -> Vec<Value> {
+            self.0.clone()
+        }
+    }
+
+    // The second connection shares the in-memory database and is used to read rows back.
+    fn duckdb() -> (Db, duckdb::Connection) {
+        let sink = DuckDbSink::connect(":memory:").unwrap();
+        let connection = sink.connection.try_clone().unwrap();
+
+        (Db::new(Box::new(sink)).unwrap(), connection)
+    }
+
+    fn field(name: &str, sql_type: &str) -> TableField {
+        TableField::new(
+            name.to_string(),
+            Some(sql_type.to_string()),
+            Some(false),
+            false,
+        )
+    }
+
+    fn key(name: &str, sql_type: &str) -> TableField {
+        TableField::new(
+            name.to_string(),
+            Some(sql_type.to_string()),
+            Some(false),
+            true,
         )
     }
+
+    fn columns(db: &Db, table: &str) -> Vec<(String, String)> {
+        db.sink
+            .table_columns(table)
+            .unwrap()
+            .into_iter()
+            .map(|c| (c.name, c.data_type))
+            .collect()
+    }
+
+    fn query(connection: &duckdb::Connection, sql: &str) -> Vec<Option<String>> {
+        connection
+            .prepare(sql)
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn table_oid(connection: &duckdb::Connection, table: &str) -> i64 {
+        connection
+            .query_row(
+                "SELECT table_oid FROM duckdb_tables() WHERE table_name = ?",
+                [table],
+                |r| r.get(0),
+            )
+            .unwrap()
+    }
+
+    fn text(value: &str) -> Value {
+        Value::Text(value.to_string())
+    }
+
+    #[test]
+    fn migrate_adds_columns_at_the_end_in_place() {
+        let (db, connection) = duckdb();
+        db.create_table("t", vec![field("a", "TEXT")]).unwrap();
+        db.append_to_table("t", vec![Row(vec![text("x")])]).unwrap();
+        let oid = table_oid(&connection, "t");
+
+        db.create_table("t", vec![field("a", "TEXT"), field("b", "BIGINT")])
+            .unwrap();
+
+        assert_eq!(
+            columns(&db, "t"),
+            vec![
+                ("a".to_string(), "VARCHAR".to_string()),
+                ("b".to_string(), "BIGINT".to_string()),
+            ]
+        );
+        assert_eq!(table_oid(&connection, "t"), oid);
+        assert_eq!(
+            query(
+                &connection,
+                "SELECT a || ',' || COALESCE(CAST(b AS VARCHAR), 'null') FROM t"
+            ),
+            vec![Some("x,null".to_string())]
+        );
+    }
+
+    #[test]
+    fn migrate_rebuilds_reordered_columns() {
+        let (db, connection) = duckdb();
+        db.create_table("t", vec![field("a", "TEXT"), field("b", "TEXT")])
+            .unwrap();
+        db.append_to_table("t", vec![Row(vec![text("x"), text("y")])])
+            .unwrap();
+
+        db.create_table("t", vec![field("b", "TEXT"), field("a", "TEXT")])
+            .unwrap();
+
+        assert_eq!(
+            columns(&db, "t")
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>(),
+            vec!["b", "a"]
+        );
+        assert_eq!(
+            query(&connection, "SELECT b || a FROM t"),
+            vec![Some("yx".to_string())]
+        );
+    }
+
+    #[test]
+    fn migrate_converts_double_to_decimal() {
+        let (db, connection) = duckdb();
+        db.create_table("t", vec![field("a", "DOUBLE")]).unwrap();
+        db.append_to_table("t", vec![Row(vec![Value::Double(1.25)])])
+            .unwrap();
+
+        db.create_table("t", vec![field("a", "DECIMAL")]).unwrap();
+
+        assert_eq!(columns(&db, "t")[0].1, "DECIMAL(38,28)");
+        assert_eq!(
+            query(&connection, "SELECT CAST(a AS VARCHAR) FROM t"),
+            vec![Some("1.2500000000000000000000000000".to_string())]
+        );
+    }
+
+    #[test]
+    fn migrate_adds_decimal_places() {
+        let (db, connection) = duckdb();
+        db.create_table("t", vec![field("a", "DECIMAL(38, 18)")])
+            .unwrap();
+        db.append_to_table("t", vec![Row(vec![Value::Decimal(Decimal::new(125, 2))])])
+            .unwrap();
+
+        db.create_table("t", vec![field("a", "DECIMAL")]).unwrap();
+
+        assert_eq!(columns(&db, "t")[0].1, "DECIMAL(38,28)");
+        assert_eq!(
+            query(&connection, "SELECT CAST(a AS VARCHAR) FROM t"),
+            vec![Some("1.2500000000000000000000000000".to_string())]
+        );
+    }
+
+    #[test]
+    fn decimals_keep_every_decimal_place() {
+        let (db, connection) = duckdb();
+        db.create_table("t", vec![field("a", "DECIMAL")]).unwrap();
+
+        let value = Decimal::from_str_exact("1.2345678901234567890123456789").unwrap();
+        assert_eq!(value.scale(), 28);
+        db.append_to_table("t", vec![Row(vec![Value::Decimal(value)])])
+            .unwrap();
+
+        assert_eq!(
+            query(&connection, "SELECT CAST(a AS VARCHAR) FROM t"),
+            vec![Some("1.2345678901234567890123456789".to_string())]
+        );
+    }
+
+    #[test]
+    fn migrate_converts_text_to_json() {
+        let (db, connection) = duckdb();
+        db.create_table("t", vec![field("a", "TEXT")]).unwrap();
+        db.append_to_table("t", vec![Row(vec![text(r#"{"b":1}"#)])])
+            .unwrap();
+
+        db.create_table("t", vec![field("a", "JSON")]).unwrap();
+
+        assert_eq!(columns(&db, "t")[0].1, "JSON");
+        assert_eq!(
+            query(&connection, "SELECT CAST(a->'b' AS VARCHAR) FROM t"),
+            vec![Some("1".to_string())]
+        );
+    }
+
+    #[test]
+    fn migrate_refuses_incompatible_types() {
+        let (db, _) = duckdb();
+        db.create_table("t", vec![field("a", "BIGINT")]).unwrap();
+
+        assert!(db.create_table("t", vec![field("a", "BOOLEAN")]).is_err());
+        assert_eq!(columns(&db, "t")[0].1, "BIGINT");
+    }
+
+    #[test]
+    fn migrate_refuses_dropped_columns() {
+        let (db, _) = duckdb();
+        db.create_table("t", vec![field("a", "TEXT"), field("b", "TEXT")])
+            .unwrap();
+
+        assert!(db.create_table("t", vec![field("a", "TEXT")]).is_err());
+        assert_eq!(columns(&db, "t").len(), 2);
+    }
+
+    #[test]
+    fn migrate_saves_a_schema_version_for_every_change() {
+        let (db, _) = duckdb();
+        let version = || db.sink.latest_schema_version("t").unwrap();
+
+        db.create_table("t", vec![field("a", "TEXT")]).unwrap();
+        assert_eq!(version(), Some(1));
+
+        // The older row keeps b as NULL through the later rebuild.
+        db.append_to_table("t", vec![Row(vec![text("x")])]).unwrap();
+
+        db.create_table("t", vec![field("a", "TEXT"), field("b", "TEXT")])
+            .unwrap();
+        assert_eq!(version(), Some(2));
+
+        db.create_table("t", vec![field("a", "TEXT"), field("b", "TEXT")])
+            .unwrap();
+        assert_eq!(version(), Some(2));
+
+        db.create_table("t", vec![field("b", "TEXT"), field("a", "TEXT")])
+            .unwrap();
+        assert_eq!(version(), Some(3));
+    }
+
+    fn upsert(db: &Db, on_conflict: OnConflict, rows: &[(&str, &str)]) {
+        let rows = rows
+            .iter()
+            .map(|(k, v)| Row(vec![text(k), text(v)]))
+            .collect();
+        db.upsert_to_table("t", &["k"], on_conflict, rows).unwrap();
+    }
+
+    fn upsert_table() -> (Db, duckdb::Connection) {
+        let (db, connection) = duckdb();
+        db.create_table("t", vec![key("k", "TEXT"), field("v", "TEXT")])
+            .unwrap();
+
+        (db, connection)
+    }
+
+    fn rows(connection: &duckdb::Connection) -> Vec<Option<String>> {
+        query(connection, "SELECT k || '=' || v FROM t ORDER BY k")
+    }
+
+    #[test]
+    fn upsert_ignore_keeps_the_first_row() {
+        let (db, connection) = upsert_table();
+
+        upsert(
+            &db,
+            OnConflict::Ignore,
+            &[("a", "1"), ("b", "1"), ("a", "2")],
+        );
+        assert_eq!(
+            rows(&connection),
+            vec![Some("a=1".to_string()), Some("b=1".to_string())]
+        );
+
+        upsert(&db, OnConflict::Ignore, &[("a", "3"), ("c", "3")]);
+        assert_eq!(
+            rows(&connection),
+            vec![
+                Some("a=1".to_string()),
+                Some("b=1".to_string()),
+                Some("c=3".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn upsert_replace_keeps_the_last_row() {
+        let (db, connection) = upsert_table();
+
+        upsert(
+            &db,
+            OnConflict::Replace,
+            &[("a", "1"), ("b", "1"), ("a", "2")],
+        );
+        assert_eq!(
+            rows(&connection),
+            vec![Some("a=2".to_string()), Some("b=1".to_string())]
+        );
+
+        upsert(
+            &db,
+            OnConflict::Replace,
+            &[("a", "3"), ("c", "3"), ("a", "4")],
+        );
+        assert_eq!(
+            rows(&connection),
+            vec![
+                Some("a=4".to_string()),
+                Some("b=1".to_string()),
+                Some("c=3".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn export_escapes_the_destination() {
+        let (db, _connection) = duckdb();
+        let output = std::env::temp_dir().join(format!("huckli export's {}", std::process::id()));
+        let seen_at = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+
+        db.create_table(
+            "exported",
+            vec![
+                field("file_source", "TEXT"),
+                field("seen_at", "TIMESTAMPTZ"),
+            ],
+        )
+        .unwrap();
+        db.append_to_table(
+            "exported",
+            vec![Row(vec![text("file-1"), Value::Timestamp(seen_at)])],
+        )
+        .unwrap();
+        db.save_file_processed("file-1", "prefix", seen_at).unwrap();
+
+        let rows = db
+            .export_table("exported", ExportFormat::Csv, &output, Some("seen_at"))
+            .unwrap();
+        let days: Vec<_> = std::fs::read_dir(output.join("exported"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        std::fs::remove_dir_all(&output).unwrap();
+
+        assert_eq!(rows, 1);
+        assert_eq!(days, vec!["day=2024-01-02"]);
+    }
+
+    #[test]
+    fn export_refuses_tables_without_a_file_source() {
+        let (db, _connection) = duckdb();
+        db.create_table("unexported", vec![field("value", "TEXT")])
+            .unwrap();
+
+        let output = std::env::temp_dir();
+        assert!(
+            db.export_table("unexported", ExportFormat::Csv, &output, None)
+                .is_err()
+        );
+        assert!(
+            db.export_table("missing", ExportFormat::Csv, &output, None)
+                .is_err()
+        );
+    }
 }
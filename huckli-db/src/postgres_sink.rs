@@ -0,0 +1,405 @@
+use std::{fmt::Write, future::Future};
+
+use chrono::{DateTime, Utc};
+use futures::SinkExt;
+
+use crate::{Column, Sink, Value};
+
+pub struct PostgresSink {
+    client: tokio_postgres::Client,
+    runtime: tokio::runtime::Handle,
+}
+
+impl PostgresSink {
+    // The sink interface is synchronous, so queries are driven on the surrounding tokio runtime.
+    // This requires a multi-threaded runtime.
+    pub fn connect(url: &str) -> anyhow::Result<Self> {
+        let runtime = tokio::runtime::Handle::try_current()?;
+        let client = tokio::task::block_in_place(|| {
+            runtime.block_on(async {
+                let (client, connection) =
+                    tokio_postgres::connect(url, tokio_postgres::NoTls).await?;
+
+                tokio::spawn(async move {
+                    if let Err(err) = connection.await {
+                        tracing::error!(?err, "postgres connection error");
+                    }
+                });
+
+                anyhow::Ok(client)
+            })
+        })?;
+
+        let sink = Self { client, runtime };
+        sink.execute_batch("SET TIME ZONE 'UTC'")?;
+
+        Ok(sink)
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        tokio::task::block_in_place(|| self.runtime.block_on(future))
+    }
+}
+
+impl Sink for PostgresSink {
+    fn execute_batch(&self, sql: &str) -> anyhow::Result<()> {
+        self.block_on(self.client.batch_execute(sql))?;
+
+        Ok(())
+    }
+
+    fn table_columns(&self, table: &str) -> anyhow::Result<Vec<Column>> {
+        let rows = self.block_on(self.client.query(
+            r#"
+                SELECT
+                    column_name::text,
+                    CASE
+                        WHEN data_type = 'numeric' AND numeric_precision IS NOT NULL
+                            THEN format('numeric(%s,%s)', numeric_precision, numeric_scale)
+                        ELSE data_type::text
+                    END,
+                    is_nullable = 'YES'
+                FROM information_schema.columns
+                WHERE table_schema = current_schema() AND table_name = $1
+                ORDER BY ordinal_position
+            "#,
+            &[&table],
+        ))?;
+
+        Ok(rows
+            .iter()
+            .map(|r| Column {
+                name: r.get(0),
+                data_type: r.get(1),
+                nullable: r.get(2),
+            })
+            .collect())
+    }
+
+    fn primary_key(&self, table: &str) -> anyhow::Result<Vec<String>> {
+        let rows = self.block_on(self.client.query(
+            r#"
+                SELECT kcu.column_name::text
+                FROM information_schema.table_constraints tc
+                JOIN information_schema.key_column_usage kcu
+                    ON kcu.constraint_name = tc.constraint_name
+                    AND kcu.table_schema = tc.table_schema
+                    AND kcu.table_name = tc.table_name
+                WHERE tc.table_schema = current_schema()
+                    AND tc.table_name = $1
+                    AND tc.constraint_type = 'PRIMARY KEY'
+                ORDER BY kcu.ordinal_position
+            "#,
+            &[&table],
+        ))?;
+
+        Ok(rows.iter().map(|r| r.get(0)).collect())
+    }
+
+    fn column_type(&self, sql_type: &str) -> String {
+        postgres_type(sql_type)
+    }
+
+    fn same_type(&self, existing: &str, sql_type: &str) -> bool {
+        same_type(existing, sql_type)
+    }
+
+    fn can_convert(&self, existing: &str, sql_type: &str) -> bool {
+        can_convert(existing, sql_type)
+    }
+
+    fn save_file_processed(
+        &self,
+        name: &str,
+        prefix: &str,
+        timestamp: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        self.block_on(self.client.execute(
+            "INSERT INTO files_processed(file_name, prefix, file_timestamp, processed_at) VALUES($1, $2, $3, $4)",
+            &[&name, &prefix, &timestamp, &Utc::now()],
+        ))?;
+
+        Ok(())
+    }
+
+    fn latest_file_processed_timestamp(&self, prefix: &str) -> anyhow::Result<DateTime<Utc>> {
+        let row = self.block_on(self.client.query_one(
+            r#"
+                SELECT file_timestamp
+                FROM files_processed
+                WHERE prefix = $1
+                ORDER BY file_timestamp DESC
+                LIMIT 1
+            "#,
+            &[&prefix],
+        ))?;
+
+        Ok(row.get(0))
+    }
+
+    fn latest_schema_version(&self, table: &str) -> anyhow::Result<Option<i32>> {
+        let row = self.block_on(self.client.query_one(
+            "SELECT MAX(version) FROM schema_versions WHERE table_name = $1",
+            &[&table],
+        ))?;
+
+        Ok(row.get(0))
+    }
+
+    fn save_schema_version(&self, table: &str, columns: &str) -> anyhow::Result<()> {
+        self.block_on(self.client.execute(
+            r#"
+                INSERT INTO schema_versions(table_name, version, columns, applied_at)
+                SELECT $1, COALESCE(MAX(version), 0) + 1, $2, $3
+                FROM schema_versions
+                WHERE table_name = $1
+            "#,
+            &[&table, &columns, &Utc::now()],
+        ))?;
+
+        Ok(())
+    }
+
+    fn append(&self, table: &str, rows: Vec<Vec<Value>>) -> anyhow::Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let mut data = String::new();
+        for row in rows {
+            let line = row.iter().map(copy_text).collect::<Vec<_>>().join("\t");
+            data.push_str(&line);
+            data.push('\n');
+        }
+
+        self.block_on(async {
+            let sink = self
+                .client
+                .copy_in::<_, bytes::Bytes>(&format!("COPY {table} FROM STDIN"))
+                .await?;
+            futures::pin_mut!(sink);
+            sink.send(bytes::Bytes::from(data)).await?;
+            sink.as_mut().finish().await?;
+
+            anyhow::Ok(())
+        })
+    }
+}
+
+fn postgres_type(sql_type: &str) -> String {
+    let sql_type = sql_type.trim().to_uppercase();
+    match sql_type.as_str() {
+        "TEXT" | "STRING" | "VARCHAR" => "TEXT".to_string(),
+        // postgres has no unsigned integers, so widen to the next type that fits
+        "UINT64" | "UBIGINT" => "NUMERIC(20, 0)".to_string(),
+        "UINT32" | "UINTEGER" | "INT64" | "BIGINT" => "BIGINT".to_string(),
+        "INT32" | "INT" | "INTEGER" => "INTEGER".to_string(),
+        "DOUBLE" | "FLOAT8" => "DOUBLE PRECISION".to_string(),
+        "BOOL" | "BOOLEAN" => "BOOLEAN".to_string(),
+        "TIMESTAMPTZ" | "TIMESTAMP WITH TIME ZONE" => "TIMESTAMPTZ".to_string(),
+        "JSON" => "JSONB".to_string(),
+        "BLOB" => "BYTEA".to_string(),
+        // unconstrained, so decimals are stored with every digit rust_decimal holds
+        "DECIMAL" | "NUMERIC" => "NUMERIC".to_string(),
+        _ => sql_type,
+    }
+}
+
+// Maps a postgres DDL type to the name reported by information_schema. Numeric columns keep
+// their precision and scale, matching how `table_columns` reports them.
+fn information_schema_type(pg_type: &str) -> String {
+    let (base, modifiers) = match pg_type.split_once('(') {
+        Some((base, modifiers)) => (base.trim(), Some(modifiers)),
+        None => (pg_type.trim(), None),
+    };
+
+    match (base, modifiers) {
+        ("TIMESTAMPTZ", _) => "TIMESTAMP WITH TIME ZONE".to_string(),
+        ("DECIMAL" | "NUMERIC", Some(modifiers)) => {
+            format!("NUMERIC({}", modifiers.replace(' ', ""))
+        }
+        ("DECIMAL", None) => "NUMERIC".to_string(),
+        _ => base.to_string(),
+    }
+}
+
+// Compares a type reported by information_schema with the type a field would be created with.
+fn same_type(existing: &str, sql_type: &str) -> bool {
+    existing.to_uppercase() == information_schema_type(&postgres_type(sql_type))
+}
+
+fn can_convert(existing: &str, sql_type: &str) -> bool {
+    let existing = existing.to_uppercase();
+    let sql_type = information_schema_type(&postgres_type(sql_type));
+    match existing.as_str() {
+        "DOUBLE PRECISION" => sql_type.starts_with("NUMERIC"),
+        "TEXT" => sql_type == "JSONB" || sql_type == "TIMESTAMP WITH TIME ZONE",
+        _ => widens_numeric(&existing, &sql_type),
+    }
+}
+
+// A numeric column can be converted when the new type keeps at least as many digits on both
+// sides of the decimal point, such as UINT64 to DECIMAL.
+fn widens_numeric(existing: &str, sql_type: &str) -> bool {
+    match (numeric_digits(existing), numeric_digits(sql_type)) {
+        (Some(_), None) => sql_type == "NUMERIC",
+        (Some((precision, scale)), Some((new_precision, new_scale))) => {
+            existing != sql_type
+                && new_scale >= scale
+                && new_precision - new_scale >= precision - scale
+        }
+        _ => false,
+    }
+}
+
+fn numeric_digits(sql_type: &str) -> Option<(u32, u32)> {
+    let (precision, scale) = sql_type
+        .strip_prefix("NUMERIC(")?
+        .strip_suffix(')')?
+        .split_once(',')?;
+
+    Some((precision.parse().ok()?, scale.parse().ok()?))
+}
+
+// Encodes a value in the COPY text format.
+fn copy_text(value: &Value) -> String {
+    match value {
+        Value::Null => "\\N".to_string(),
+        Value::Bool(v) => if *v { "t" } else { "f" }.to_string(),
+        Value::Int32(v) => v.to_string(),
+        Value::UInt32(v) => v.to_string(),
+        Value::Int64(v) => v.to_string(),
+        Value::UInt64(v) => v.to_string(),
+        Value::Double(v) if v.is_infinite() && *v > 0.0 => "Infinity".to_string(),
+        Value::Double(v) if v.is_infinite() => "-Infinity".to_string(),
+        Value::Double(v) => v.to_string(),
+        Value::Decimal(v) => v.to_string(),
+        Value::Text(v) => escape_copy_text(v),
+        Value::Json(v) => escape_copy_text(&v.to_string()),
+        Value::Timestamp(v) => v.to_rfc3339(),
+        Value::Blob(v) => v.iter().fold("\\\\x".to_string(), |mut hex, b| {
+            let _ = write!(hex, "{b:02x}");
+            hex
+        }),
+    }
+}
+
+fn escape_copy_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use rust_decimal::Decimal;
+
+    use super::*;
+
+    #[test]
+    fn copy_text_encodes_values() {
+        assert_eq!(copy_text(&Value::Null), "\\N");
+        assert_eq!(copy_text(&Value::Bool(true)), "t");
+        assert_eq!(copy_text(&Value::Bool(false)), "f");
+        assert_eq!(copy_text(&Value::UInt64(u64::MAX)), "18446744073709551615");
+        assert_eq!(copy_text(&Value::Double(1.5)), "1.5");
+        assert_eq!(copy_text(&Value::Double(f64::INFINITY)), "Infinity");
+        assert_eq!(copy_text(&Value::Double(f64::NEG_INFINITY)), "-Infinity");
+        assert_eq!(copy_text(&Value::Decimal(Decimal::new(125, 2))), "1.25");
+        assert_eq!(
+            copy_text(&Value::Timestamp(
+                Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()
+            )),
+            "2024-01-02T03:04:05+00:00"
+        );
+        assert_eq!(
+            copy_text(&Value::Json(serde_json::json!({"a": "b\tc"}))),
+            r#"{"a":"b\\tc"}"#
+        );
+    }
+
+    #[test]
+    fn copy_text_encodes_blobs_as_escaped_hex() {
+        assert_eq!(
+            copy_text(&Value::Blob(vec![0x00, 0xab, 0xff])),
+            "\\\\x00abff"
+        );
+        assert_eq!(copy_text(&Value::Blob(vec![])), "\\\\x");
+    }
+
+    #[test]
+    fn escape_copy_text_escapes_delimiters() {
+        assert_eq!(escape_copy_text("plain"), "plain");
+        assert_eq!(escape_copy_text("a\tb"), "a\\tb");
+        assert_eq!(escape_copy_text("a\nb\r"), "a\\nb\\r");
+        assert_eq!(escape_copy_text("a\\b"), "a\\\\b");
+        assert_eq!(escape_copy_text("\\N"), "\\\\N");
+    }
+
+    #[test]
+    fn postgres_type_maps_sql_types() {
+        assert_eq!(postgres_type("text"), "TEXT");
+        assert_eq!(postgres_type(" varchar "), "TEXT");
+        assert_eq!(postgres_type("UINT64"), "NUMERIC(20, 0)");
+        assert_eq!(postgres_type("uint32"), "BIGINT");
+        assert_eq!(postgres_type("INTEGER"), "INTEGER");
+        assert_eq!(postgres_type("DOUBLE"), "DOUBLE PRECISION");
+        assert_eq!(postgres_type("TIMESTAMPTZ"), "TIMESTAMPTZ");
+        assert_eq!(postgres_type("JSON"), "JSONB");
+        assert_eq!(postgres_type("BLOB"), "BYTEA");
+        assert_eq!(postgres_type("DECIMAL"), "NUMERIC");
+        assert_eq!(postgres_type("DECIMAL(10, 2)"), "DECIMAL(10, 2)");
+    }
+
+    #[test]
+    fn information_schema_type_keeps_numeric_modifiers() {
+        assert_eq!(information_schema_type("NUMERIC(38, 18)"), "NUMERIC(38,18)");
+        assert_eq!(information_schema_type("DECIMAL(10, 2)"), "NUMERIC(10,2)");
+        assert_eq!(information_schema_type("DECIMAL"), "NUMERIC");
+        assert_eq!(
+            information_schema_type("TIMESTAMPTZ"),
+            "TIMESTAMP WITH TIME ZONE"
+        );
+        assert_eq!(information_schema_type("BIGINT"), "BIGINT");
+    }
+
+    #[test]
+    fn same_type_matches_information_schema_names() {
+        assert!(same_type("text", "VARCHAR"));
+        assert!(same_type("numeric(20,0)", "UINT64"));
+        assert!(same_type("numeric", "DECIMAL"));
+        assert!(same_type("numeric(10,2)", "DECIMAL(10, 2)"));
+        assert!(same_type("bigint", "UINT32"));
+        assert!(same_type("double precision", "DOUBLE"));
+        assert!(same_type("timestamp with time zone", "TIMESTAMPTZ"));
+        assert!(same_type("jsonb", "JSON"));
+        assert!(same_type("bytea", "BLOB"));
+        assert!(!same_type("text", "JSON"));
+        assert!(!same_type("bigint", "UINT64"));
+        assert!(!same_type("double precision", "DECIMAL"));
+        assert!(!same_type("numeric(20,0)", "DECIMAL"));
+        assert!(!same_type("numeric(38,18)", "UINT64"));
+        assert!(!same_type("numeric(38,18)", "DECIMAL"));
+        assert!(!same_type("numeric", "UINT64"));
+    }
+
+    #[test]
+    fn can_convert_only_widens() {
+        assert!(can_convert("double precision", "DECIMAL"));
+        assert!(can_convert("text", "JSON"));
+        assert!(can_convert("text", "TIMESTAMPTZ"));
+        assert!(can_convert("numeric(20,0)", "DECIMAL"));
+        assert!(can_convert("numeric(10,2)", "DECIMAL"));
+        assert!(can_convert("numeric(38,18)", "DECIMAL"));
+        assert!(can_convert("numeric(10,2)", "DECIMAL(12, 2)"));
+
+        assert!(!can_convert("numeric(38,18)", "UINT64"));
+        assert!(!can_convert("numeric", "DECIMAL"));
+        assert!(!can_convert("numeric(10,2)", "DECIMAL(10, 1)"));
+        assert!(!can_convert("numeric", "UINT64"));
+        assert!(!can_convert("bigint", "BOOLEAN"));
+    }
+}
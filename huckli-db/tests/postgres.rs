@@ -0,0 +1,226 @@
+// Needs a running postgres, so it is ignored by default. Run it against the database in
+// DATABASE_URL with
+// DATABASE_URL=postgres://postgres@localhost/postgres cargo test -p huckli-db --test postgres -- --ignored
+
+use chrono::{TimeZone, Utc};
+use huckli_db::{Appendable, Db, OnConflict, TableField, Value};
+use rust_decimal::Decimal;
+
+const TABLE: &str = "huckli_db_postgres_test";
+
+// rust_decimal keeps up to 28 decimal places, all of which must survive the round trip.
+const AMOUNT: &str = "1.2345678901234567890123456789";
+
+struct Row(Vec<Value>);
+
+impl Appendable for Row {
+    fn row(&self) -> Vec<Value> {
+        self.0.clone()
+    }
+}
+
+fn field(name: &str, sql_type: &str, nullable: bool, key: bool) -> TableField {
+    TableField::new(
+        name.to_string(),
+        Some(sql_type.to_string()),
+        Some(nullable),
+        key,
+    )
+}
+
+fn fields() -> Vec<TableField> {
+    fields_with("UINT64")
+}
+
+fn fields_with(location_type: &str) -> Vec<TableField> {
+    vec![
+        field("id", "TEXT", false, true),
+        field("name", "TEXT", false, false),
+        field("amount", "DECIMAL", false, false),
+        field("data", "BLOB", false, false),
+        field("payload", "JSON", false, false),
+        field("seen_at", "TIMESTAMPTZ", false, false),
+        field("score", "DOUBLE", false, false),
+        field("active", "BOOLEAN", false, false),
+        field("location", location_type, false, false),
+        field("note", "TEXT", true, false),
+    ]
+}
+
+// The fields after the `added` column migration, with `location` created as `location_type`.
+fn migrated_fields(location_type: &str) -> Vec<TableField> {
+    let mut fields = fields_with(location_type);
+    fields.push(field("added", "BIGINT", false, false));
+    fields
+}
+
+fn row(id: &str, name: &str, score: f64, note: Option<&str>) -> Row {
+    Row(vec![
+        Value::Text(id.to_string()),
+        Value::Text(name.to_string()),
+        Value::Decimal(Decimal::from_str_exact(AMOUNT).unwrap()),
+        Value::Blob(vec![0x00, 0x5c, 0xff]),
+        Value::Json(serde_json::json!({"tab": "a\tb"})),
+        Value::Timestamp(Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap()),
+        Value::Double(score),
+        Value::Bool(true),
+        Value::UInt64(u64::MAX),
+        note.map_or(Value::Null, |n| Value::Text(n.to_string())),
+    ])
+}
+
+async fn client(url: &str) -> tokio_postgres::Client {
+    let (client, connection) = tokio_postgres::connect(url, tokio_postgres::NoTls)
+        .await
+        .unwrap();
+    tokio::spawn(connection);
+    client
+}
+
+async fn rows(client: &tokio_postgres::Client, columns: &str) -> Vec<Vec<Option<String>>> {
+    client
+        .query(&format!("SELECT {columns} FROM {TABLE} ORDER BY id"), &[])
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| (0..r.len()).map(|i| r.get(i)).collect())
+        .collect()
+}
+
+async fn schema_version(client: &tokio_postgres::Client) -> Option<i32> {
+    client
+        .query_one(
+            "SELECT MAX(version) FROM schema_versions WHERE table_name = $1",
+            &[&TABLE],
+        )
+        .await
+        .unwrap()
+        .get(0)
+}
+
+fn text(values: &[Option<&str>]) -> Vec<Option<String>> {
+    values.iter().map(|v| v.map(str::to_string)).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs a postgres database in DATABASE_URL"]
+async fn postgres_round_trip() {
+    let url =
+        std::env::var("DATABASE_URL").expect("DATABASE_URL must point to a postgres database");
+
+    let client = client(&url).await;
+    let db = Db::connect(&url).unwrap();
+    client
+        .batch_execute(&format!(
+            "DROP TABLE IF EXISTS {TABLE}; DELETE FROM schema_versions WHERE table_name = '{TABLE}';"
+        ))
+        .await
+        .unwrap();
+
+    db.create_table(TABLE, fields()).unwrap();
+    assert_eq!(schema_version(&client).await, Some(1));
+
+    db.append_to_table(
+        TABLE,
+        vec![
+            row("a", "tab\there", f64::INFINITY, None),
+            row("b", "line\nbreak \\ slash", f64::NEG_INFINITY, Some("\\N")),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(
+        rows(
+            &client,
+            "id, name, amount::text, encode(data, 'hex'), payload->>'tab', \
+             to_char(seen_at, 'YYYY-MM-DD HH24:MI:SS'), score::text, active::text, \
+             location::text, note"
+        )
+        .await,
+        vec![
+            text(&[
+                Some("a"),
+                Some("tab\there"),
+                Some(AMOUNT),
+                Some("005cff"),
+                Some("a\tb"),
+                Some("2024-01-02 03:04:05"),
+                Some("Infinity"),
+                Some("true"),
+                Some("18446744073709551615"),
+                None,
+            ]),
+            text(&[
+                Some("b"),
+                Some("line\nbreak \\ slash"),
+                Some(AMOUNT),
+                Some("005cff"),
+                Some("a\tb"),
+                Some("2024-01-02 03:04:05"),
+                Some("-Infinity"),
+                Some("true"),
+                Some("18446744073709551615"),
+                Some("\\N"),
+            ]),
+        ]
+    );
+
+    db.upsert_to_table(
+        TABLE,
+        &["id"],
+        OnConflict::Replace,
+        vec![
+            row("a", "first", 1.0, None),
+            row("c", "new", 2.0, None),
+            row("a", "last", 3.0, None),
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(
+        rows(&client, "id, name, score::text").await,
+        vec![
+            text(&[Some("a"), Some("last"), Some("3")]),
+            text(&[Some("b"), Some("line\nbreak \\ slash"), Some("-Infinity")]),
+            text(&[Some("c"), Some("new"), Some("2")]),
+        ]
+    );
+
+    db.create_table(TABLE, fields()).unwrap();
+    assert_eq!(schema_version(&client).await, Some(1));
+
+    db.create_table(TABLE, migrated_fields("UINT64")).unwrap();
+    assert_eq!(schema_version(&client).await, Some(2));
+
+    assert_eq!(
+        rows(&client, "id, added::text").await,
+        vec![
+            text(&[Some("a"), None]),
+            text(&[Some("b"), None]),
+            text(&[Some("c"), None]),
+        ]
+    );
+
+    let widened = migrated_fields("DECIMAL");
+    db.create_table(TABLE, widened).unwrap();
+    assert_eq!(schema_version(&client).await, Some(3));
+    assert_eq!(
+        rows(&client, "id, location::text").await,
+        vec![
+            text(&[Some("a"), Some("18446744073709551615")]),
+            text(&[Some("b"), Some("18446744073709551615")]),
+            text(&[Some("c"), Some("18446744073709551615")]),
+        ]
+    );
+
+    let narrowed = migrated_fields("UINT64");
+    assert!(db.create_table(TABLE, narrowed).is_err());
+    assert_eq!(schema_version(&client).await, Some(3));
+
+    client
+        .batch_execute(&format!(
+            "DROP TABLE {TABLE}; DELETE FROM schema_versions WHERE table_name = '{TABLE}';"
+        ))
+        .await
+        .unwrap();
+}
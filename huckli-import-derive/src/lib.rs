@@ -8,44 +8,75 @@ use syn::{parse_macro_input, DeriveInput};
 #[darling(attributes(import))]
 struct Field {
     ident: Option<syn::Ident>,
+    ty: syn::Type,
     sql: Option<String>,
     nullable: Option<bool>,
     #[darling(default)]
     skip: bool,
+    #[darling(default)]
+    key: bool,
+    #[darling(default)]
+    children: bool,
+    foreign_key: Option<String>,
+    table_name: Option<String>,
+}
+
+impl Field {
+    // An explicit `sql` attribute wins over the type inferred from the field.
+    fn sql_type(&self) -> Option<String> {
+        self.sql
+            .clone()
+            .or_else(|| infer_sql_type(&self.ty).map(str::to_string))
+    }
+
+    fn is_nullable(&self) -> bool {
+        self.nullable
+            .unwrap_or_else(|| option_inner_type(&self.ty).is_some())
+    }
 }
 
 impl ToTokens for Field {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let name = self.ident.as_ref().unwrap().to_string();
-        let sql = if let Some(t) = self.sql.as_ref() {
-            quote! { Some(#t.to_string()) }
-        } else {
-            quote! { None }
-        };
-        let nullable = if let Some(n) = self.nullable {
-            quote! { Some(#n) }
-        } else {
-            quote! { None }
-        };
+        let sql = self.sql_type().unwrap_or_default();
+        let nullable = self.is_nullable();
+        let key = self.key;
 
-        tokens.extend(quote! { huckli_db::TableField::new(#name.to_string(), #sql, #nullable) });
+        tokens.extend(
+            quote! { huckli_db::TableField::new(#name.to_string(), Some(#sql.to_string()), Some(#nullable), #key) },
+        );
     }
 }
 
+#[derive(Debug, darling::FromVariant, Clone)]
+struct Variant {
+    ident: syn::Ident,
+    fields: darling::ast::Fields<VariantField>,
+}
+
+#[derive(Debug, darling::FromField, Clone)]
+struct VariantField {
+    ty: syn::Type,
+}
+
 #[derive(Debug, FromMeta)]
 struct S3Decode {
     proto: syn::Ident,
     bucket: String,
     prefix: String,
+    name: Option<String>,
 }
 
 #[derive(Debug, darling::FromDeriveInput)]
-#[darling(attributes(import), supports(struct_any))]
+#[darling(attributes(import), supports(struct_any, enum_any))]
 struct PersistDeriveOpts {
     ident: syn::Ident,
-    data: darling::ast::Data<(), Field>,
+    data: darling::ast::Data<Variant, Field>,
     s3decode: Option<S3Decode>,
     table_name: Option<String>,
+    on_conflict: Option<String>,
+    #[darling(default)]
+    child: bool,
 }
 
 #[proc_macro_derive(Import, attributes(import))]
@@ -58,6 +89,11 @@ pub fn persist_derive(input: TokenStream) -> TokenStream {
     };
 
     let name = opts.ident.clone();
+    let decode = s3decode(&name, opts.s3decode.as_ref());
+
+    if let darling::ast::Data::Enum(variants) = &opts.data {
+        return enum_derive(&name, variants, decode).into();
+    }
 
     let table_name = opts
         .table_name
@@ -73,66 +109,543 @@ pub fn persist_derive(input: TokenStream) -> TokenStream {
         .filter(|f| !f.skip)
         .collect::<Vec<_>>();
 
+    let (children, fields): (Vec<_>, Vec<_>) = fields.into_iter().partition(|f| f.children);
+
+    if opts.child && (opts.s3decode.is_some() || !children.is_empty()) {
+        return syn::Error::new_spanned(
+            &opts.ident,
+            "child types are saved through their parent and cannot use s3decode or children",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    if let Some(field) = fields
+        .iter()
+        .find(|f| f.foreign_key.is_some() || f.table_name.is_some())
+    {
+        return syn::Error::new_spanned(
+            field.ident.as_ref().unwrap(),
+            "foreign_key and table_name can only be set on children fields",
+        )
+        .to_compile_error()
+        .into();
+    }
+
     let field_names = fields.iter().map(|f| f.ident.clone()).collect::<Vec<_>>();
 
+    for field in &fields {
+        if field.sql_type().is_none() {
+            return syn::Error::new_spanned(
+                &field.ty,
+                "cannot infer a SQL type for this field, add #[import(sql = \"...\")]",
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
+    for field in fields.iter().filter(|f| f.key) {
+        if field.is_nullable() {
+            return syn::Error::new_spanned(
+                field.ident.as_ref().unwrap(),
+                "key fields cannot be nullable",
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
+    let keys = fields
+        .iter()
+        .filter(|f| f.key)
+        .map(|f| f.ident.as_ref().unwrap().to_string())
+        .collect::<Vec<_>>();
+
+    // Children are always appended, so re-importing a parent that is skipped or replaced on
+    // conflict would duplicate its children.
+    if !keys.is_empty() && !children.is_empty() {
+        return syn::Error::new_spanned(
+            &opts.ident,
+            "types with key fields cannot have children, which would be duplicated on re-import",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let on_conflict = match opts.on_conflict.as_deref() {
+        None | Some("ignore") => quote! { huckli_db::OnConflict::Ignore },
+        Some("replace") => quote! { huckli_db::OnConflict::Replace },
+        Some(other) => {
+            return syn::Error::new_spanned(
+                &opts.ident,
+                format!("unknown on_conflict value {other}, expected ignore or replace"),
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let save = if keys.is_empty() {
+        quote! { db.append_to_table(#table_name, data) }
+    } else {
+        quote! { db.upsert_to_table(#table_name, &[#(#keys),*], #on_conflict, data) }
+    };
+
+    let mut child_names = Vec::new();
+    let mut child_types = Vec::new();
+    let mut foreign_keys = Vec::new();
+    let mut foreign_key_columns = Vec::new();
+    let mut foreign_key_checks = Vec::new();
+    let mut child_tables = Vec::new();
+    for child in &children {
+        let Some(child_type) = vec_inner_type(&child.ty) else {
+            return syn::Error::new_spanned(&child.ty, "children fields must be a Vec")
+                .to_compile_error()
+                .into();
+        };
+
+        let foreign_key = child
+            .foreign_key
+            .clone()
+            .unwrap_or_else(|| "id".to_string());
+        let Some(parent_field) = fields
+            .iter()
+            .find(|f| f.ident.as_ref().is_some_and(|i| i == &foreign_key))
+        else {
+            return syn::Error::new_spanned(
+                child.ident.as_ref().unwrap(),
+                format!("foreign key {foreign_key} is not a column of {name}"),
+            )
+            .to_compile_error()
+            .into();
+        };
+
+        let sql = parent_field.sql_type().unwrap_or_default();
+        let nullable = parent_field.is_nullable();
+        child_names.push(child.ident.clone());
+        child_types.push(child_type.clone());
+        // The child's own table name is used unless the parent picks one, which lets one child
+        // type be stored in a separate table for each parent.
+        child_tables.push(match &child.table_name {
+            Some(table) => quote! { #table },
+            None => quote! { <#child_type as huckli_db::Table>::NAME },
+        });
+        foreign_keys.push(parent_field.ident.clone());
+        foreign_key_columns.push(quote! {
+            huckli_db::TableField::new(#foreign_key.to_string(), Some(#sql.to_string()), Some(#nullable), false)
+        });
+
+        // The foreign key is prepended to the child's own columns, so it must not share a name
+        // with any of them.
+        let message = format!(
+            "{} already has a column named {foreign_key}, set a different foreign_key on {name}.{}",
+            child_type.to_token_stream(),
+            child.ident.as_ref().unwrap(),
+        );
+        foreign_key_checks.push(quote! {
+            const _: () = assert!(
+                !huckli_db::has_column(<#child_type as huckli_db::Table>::COLUMNS, #foreign_key),
+                #message
+            );
+        });
+    }
+
+    // Children are taken out of their parents and saved in one batch per child table, with the
+    // parent's foreign key prepended to each row.
+    let save = if children.is_empty() {
+        save
+    } else {
+        quote! {
+            let mut data = data;
+            #(let mut #child_names = Vec::new();)*
+            for parent in data.iter_mut() {
+                #(
+                    let key = huckli_db::ToValue::to_value(&parent.#foreign_keys);
+                    #child_names.extend(
+                        std::mem::take(&mut parent.#child_names)
+                            .into_iter()
+                            .map(|child| (key.clone(), child)),
+                    );
+                )*
+            }
+
+            #save?;
+            #(db.append_children(#child_tables, #child_names)?;)*
+
+            Ok(())
+        }
+    };
+
+    // Child types only get a table through their parent, which adds the foreign key column.
+    let db_table = if opts.child {
+        quote! {}
+    } else {
+        quote! {
+            impl crate::DbTable for #name {
+                fn create_table(db: &huckli_db::Db) -> anyhow::Result<()> {
+                    db.create_table(#table_name, <Self as huckli_db::Table>::fields())?;
+                    #(db.create_child_table::<#child_types>(#child_tables, #foreign_key_columns)?;)*
+
+                    Ok(())
+                }
+
+                fn table_names() -> Vec<&'static str> {
+                    vec![#table_name, #(#child_tables),*]
+                }
+
+                fn save(db: &huckli_db::Db, data: Vec<Self>) -> anyhow::Result<()> {
+                    #save
+                }
+            }
+        }
+    };
+
+    let column_names = field_names
+        .iter()
+        .map(|f| f.as_ref().unwrap().to_string())
+        .collect::<Vec<_>>();
+
     let persist = quote! {
-        impl crate::DbTable for #name {
-            fn create_table(db: &huckli_db::Db) -> anyhow::Result<()> {
-                let fields = vec![
+        #db_table
+
+        #(#foreign_key_checks)*
+
+        impl huckli_db::Table for #name {
+            const NAME: &'static str = #table_name;
+
+            const COLUMNS: &'static [&'static str] = &[#(#column_names),*, "file_source"];
+
+            fn fields() -> Vec<huckli_db::TableField> {
+                vec![
                     #(#fields),*,
                     huckli_db::TableField::new(
                         "file_source".to_string(),
                         Some("TEXT".to_string()),
-                        Some(false)
+                        Some(false),
+                        false
                     )
-                ];
-                db.create_table(#table_name, fields)
-            }
-
-            fn save(db: &huckli_db::Db, data: Vec<Self>) -> anyhow::Result<()> {
-                db.append_to_table(#table_name, data)
+                ]
             }
         }
 
         impl huckli_db::Appendable for #name {
-            fn append(&self, appender: &mut duckdb::Appender) -> anyhow::Result<()> {
+            fn row(&self) -> Vec<huckli_db::Value> {
                 let file_source = crate::get_file_source()
                     .unwrap_or_else(|| "unknown".to_string());
-                appender.append_row(duckdb::params![#(self.#field_names),*, file_source])
-                    .map_err(anyhow::Error::from)
+                vec![
+                    #(huckli_db::ToValue::to_value(&self.#field_names)),*,
+                    huckli_db::Value::Text(file_source),
+                ]
             }
         }
 
     };
 
-    let decode = if let Some(s3decode) = opts.s3decode {
-        let proto = s3decode.proto;
-        let bucket = s3decode.bucket;
-        let prefix = s3decode.prefix;
-        quote! {
-            impl #name {
-                pub async fn get_and_persist(
-                    db: &huckli_db::Db,
-                    s3: &huckli_s3::S3,
-                    selection: &crate::FileSelectionArgs,
-                ) -> anyhow::Result<()> {
-                    crate::get_and_persist::<#proto, #name>(
-                        db,
-                        s3,
-                        #bucket,
-                        #prefix,
-                        selection,
-                    ).await
-                }
+    quote! {
+        #persist
+        #decode
+    }
+    .into()
+}
+
+fn s3decode(name: &syn::Ident, s3decode: Option<&S3Decode>) -> proc_macro2::TokenStream {
+    let Some(S3Decode {
+        proto,
+        bucket,
+        prefix,
+        name: file_type,
+    }) = s3decode
+    else {
+        return quote! {};
+    };
+
+    // The registered name is what the CLI accepts, it defaults to the type name in kebab case.
+    let file_type = file_type
+        .clone()
+        .unwrap_or_else(|| name.to_string().to_snake().to_dashed());
+    let proto_name = proto.to_string();
+
+    quote! {
+        impl #name {
+            pub async fn get_and_persist(
+                db: &huckli_db::Db,
+                s3: &huckli_s3::S3,
+                selection: &crate::FileSelectionArgs,
+            ) -> anyhow::Result<()> {
+                crate::get_and_persist(
+                    db,
+                    s3,
+                    #bucket,
+                    #prefix,
+                    selection,
+                    crate::decode_proto::<#proto, #name>,
+                ).await
+            }
+        }
+
+        inventory::submit! {
+            crate::FileType {
+                name: #file_type,
+                bucket: #bucket,
+                prefix: #prefix,
+                proto: #proto_name,
+                table_names: <#name as crate::DbTable>::table_names,
+                get_and_persist: |db, s3, selection| Box::pin(#name::get_and_persist(db, s3, selection)),
+            }
+        }
+    }
+}
+
+// Enums hold one `Import` type per variant and are saved by routing each value to its variant's
+// tables in a single pass. Unit variants stand for data that is not imported and are counted.
+fn enum_derive(
+    name: &syn::Ident,
+    variants: &[Variant],
+    decode: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let mut idents = Vec::new();
+    let mut types = Vec::new();
+    let mut ignored = Vec::new();
+    for variant in variants {
+        match variant.fields.fields.as_slice() {
+            [] => ignored.push(variant.ident.clone()),
+            [field] if variant.fields.style == darling::ast::Style::Tuple => {
+                idents.push(variant.ident.clone());
+                types.push(field.ty.clone());
+            }
+            _ => {
+                return syn::Error::new_spanned(
+                    &variant.ident,
+                    "variants must be unit variants or hold a single Import type",
+                )
+                .to_compile_error();
             }
         }
+    }
+
+    let rows = idents
+        .iter()
+        .map(|i| quote::format_ident!("{}_rows", i.to_string().to_snake()))
+        .collect::<Vec<_>>();
+
+    let (count_ignored, report_ignored) = if ignored.is_empty() {
+        (quote! {}, quote! {})
     } else {
-        quote! {}
+        let message = format!("skipped ignored {name} variants");
+        (
+            quote! { let mut ignored = 0; },
+            quote! {
+                if ignored > 0 {
+                    tracing::warn!(
+                        file = crate::get_file_source().unwrap_or_default(),
+                        count = ignored,
+                        #message
+                    );
+                }
+            },
+        )
     };
 
     quote! {
-        #persist
+        impl crate::DbTable for #name {
+            fn create_table(db: &huckli_db::Db) -> anyhow::Result<()> {
+                #(<#types as crate::DbTable>::create_table(db)?;)*
+
+                Ok(())
+            }
+
+            fn table_names() -> Vec<&'static str> {
+                [#(<#types as crate::DbTable>::table_names()),*].concat()
+            }
+
+            fn save(db: &huckli_db::Db, data: Vec<Self>) -> anyhow::Result<()> {
+                #count_ignored
+                #(let mut #rows = Vec::new();)*
+
+                for value in data {
+                    match value {
+                        #(#name::#idents(v) => #rows.push(v),)*
+                        #(#name::#ignored => ignored += 1,)*
+                    }
+                }
+
+                #report_ignored
+
+                #(<#types as crate::DbTable>::save(db, #rows)?;)*
+
+                Ok(())
+            }
+        }
+
         #decode
     }
-    .into()
+}
+
+// Maps a field's Rust type to the SQL type of its column. `Option<T>` maps to the type of `T`.
+fn infer_sql_type(ty: &syn::Type) -> Option<&'static str> {
+    let ty = option_inner_type(ty).unwrap_or(ty);
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+
+    let sql = match segment.ident.to_string().as_str() {
+        "String" => "TEXT",
+        "bool" => "BOOLEAN",
+        "i32" => "INTEGER",
+        "i64" => "BIGINT",
+        "u32" => "UINTEGER",
+        "u64" => "UBIGINT",
+        "f64" => "DOUBLE",
+        "Decimal" => "DECIMAL",
+        "DateTime" if generic_argument(segment).is_some_and(|t| is_type(t, "Utc")) => "TIMESTAMPTZ",
+        "Value" if path.path.segments.len() == 2 && path.path.segments[0].ident == "serde_json" => {
+            "JSON"
+        }
+        "Vec" if generic_argument(segment).is_some_and(|t| is_type(t, "u8")) => "BLOB",
+        _ => return None,
+    };
+
+    Some(sql)
+}
+
+fn option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    wrapped_type(ty, "Option")
+}
+
+fn vec_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
+    wrapped_type(ty, "Vec")
+}
+
+fn wrapped_type<'a>(ty: &'a syn::Type, wrapper: &str) -> Option<&'a syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+
+    if segment.ident == wrapper {
+        generic_argument(segment)
+    } else {
+        None
+    }
+}
+
+fn generic_argument(segment: &syn::PathSegment) -> Option<&syn::Type> {
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    match args.args.first()? {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+fn is_type(ty: &syn::Type, name: &str) -> bool {
+    matches!(ty, syn::Type::Path(path) if path.path.is_ident(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use darling::FromField;
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn sql(ty: syn::Type) -> Option<&'static str> {
+        infer_sql_type(&ty)
+    }
+
+    fn fields(input: syn::DeriveInput) -> Vec<Field> {
+        let syn::Data::Struct(data) = input.data else {
+            unreachable!("test inputs are structs");
+        };
+
+        data.fields
+            .iter()
+            .map(|f| Field::from_field(f).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn infers_sql_types() {
+        assert_eq!(sql(parse_quote!(String)), Some("TEXT"));
+        assert_eq!(sql(parse_quote!(bool)), Some("BOOLEAN"));
+        assert_eq!(sql(parse_quote!(i32)), Some("INTEGER"));
+        assert_eq!(sql(parse_quote!(i64)), Some("BIGINT"));
+        assert_eq!(sql(parse_quote!(u32)), Some("UINTEGER"));
+        assert_eq!(sql(parse_quote!(u64)), Some("UBIGINT"));
+        assert_eq!(sql(parse_quote!(f64)), Some("DOUBLE"));
+        assert_eq!(sql(parse_quote!(rust_decimal::Decimal)), Some("DECIMAL"));
+        assert_eq!(sql(parse_quote!(DateTime<Utc>)), Some("TIMESTAMPTZ"));
+        assert_eq!(
+            sql(parse_quote!(chrono::DateTime<Utc>)),
+            Some("TIMESTAMPTZ")
+        );
+        assert_eq!(sql(parse_quote!(serde_json::Value)), Some("JSON"));
+        assert_eq!(sql(parse_quote!(Vec<u8>)), Some("BLOB"));
+    }
+
+    #[test]
+    fn does_not_guess_unknown_types() {
+        assert_eq!(sql(parse_quote!(u16)), None);
+        assert_eq!(sql(parse_quote!(DateTime<Local>)), None);
+        assert_eq!(sql(parse_quote!(Value)), None);
+        assert_eq!(sql(parse_quote!(Vec<u64>)), None);
+        assert_eq!(sql(parse_quote!((u64, u64))), None);
+    }
+
+    #[test]
+    fn options_map_to_their_inner_type() {
+        assert_eq!(sql(parse_quote!(Option<u64>)), Some("UBIGINT"));
+        assert_eq!(
+            sql(parse_quote!(Option<DateTime<Utc>>)),
+            Some("TIMESTAMPTZ")
+        );
+        assert_eq!(sql(parse_quote!(Option<Vec<u8>>)), Some("BLOB"));
+        assert_eq!(sql(parse_quote!(Option<u16>)), None);
+
+        assert!(
+            option_inner_type(&parse_quote!(Option<String>)).is_some_and(|t| is_type(t, "String"))
+        );
+        assert!(option_inner_type(&parse_quote!(String)).is_none());
+        assert!(vec_inner_type(&parse_quote!(Vec<Kid>)).is_some_and(|t| is_type(t, "Kid")));
+        assert!(vec_inner_type(&parse_quote!(Option<Kid>)).is_none());
+    }
+
+    #[test]
+    fn attributes_override_inferred_columns() {
+        let fields = fields(parse_quote! {
+            struct Row {
+                plain: u64,
+                optional: Option<u64>,
+                #[import(sql = "bigint")]
+                overridden: u64,
+                #[import(nullable = false)]
+                required: Option<String>,
+                #[import(nullable = true)]
+                relaxed: String,
+                #[import(sql = "TEXT")]
+                unknown: u16,
+            }
+        });
+
+        let columns = fields
+            .iter()
+            .map(|f| (f.sql_type(), f.is_nullable()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            columns,
+            vec![
+                (Some("UBIGINT".to_string()), false),
+                (Some("UBIGINT".to_string()), true),
+                (Some("bigint".to_string()), false),
+                (Some("TEXT".to_string()), false),
+                (Some("TEXT".to_string()), true),
+                (Some("TEXT".to_string()), false),
+            ]
+        );
+    }
 }
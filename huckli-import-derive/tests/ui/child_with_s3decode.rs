@@ -0,0 +1,9 @@
+use huckli_import_derive::Import;
+
+#[derive(Import)]
+#[import(child, s3decode(proto = Proto, bucket = "bucket", prefix = "prefix"))]
+struct ChildWithS3Decode {
+    value: u64,
+}
+
+fn main() {}
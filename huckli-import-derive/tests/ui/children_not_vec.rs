@@ -0,0 +1,14 @@
+use huckli_import_derive::Import;
+
+struct Kid {
+    value: u64,
+}
+
+#[derive(Import)]
+struct ChildrenNotVec {
+    id: String,
+    #[import(children)]
+    kid: Option<Kid>,
+}
+
+fn main() {}
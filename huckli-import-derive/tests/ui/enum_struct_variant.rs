@@ -0,0 +1,9 @@
+use huckli_import_derive::Import;
+
+#[derive(Import)]
+enum StructVariant {
+    Row { id: String },
+    Unknown,
+}
+
+fn main() {}
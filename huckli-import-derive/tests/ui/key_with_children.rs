@@ -0,0 +1,15 @@
+use huckli_import_derive::Import;
+
+struct Kid {
+    value: u64,
+}
+
+#[derive(Import)]
+struct KeyWithChildren {
+    #[import(key)]
+    id: String,
+    #[import(children)]
+    kids: Vec<Kid>,
+}
+
+fn main() {}
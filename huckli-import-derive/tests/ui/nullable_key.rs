@@ -0,0 +1,10 @@
+use huckli_import_derive::Import;
+
+#[derive(Import)]
+struct NullableKey {
+    #[import(key)]
+    id: Option<String>,
+    value: u64,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use huckli_import_derive::Import;
+
+#[derive(Import)]
+struct TableNameWithoutChildren {
+    #[import(table_name = "ids")]
+    id: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use huckli_import_derive::Import;
+
+#[derive(Import)]
+struct Unknown {
+    id: String,
+    elapsed: std::time::Duration,
+}
+
+fn main() {}
@@ -0,0 +1,66 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+// Compiles the helium-proto sources into a descriptor set so raw imports can decode any message
+// without the user building one with protoc.
+fn main() {
+    let proto_dir = helium_proto_dir();
+    println!("cargo:rerun-if-changed={}", proto_dir.display());
+
+    let mut files = Vec::new();
+    proto_files(&proto_dir, &mut files);
+    files.sort();
+
+    let out = PathBuf::from(std::env::var_os("OUT_DIR").unwrap()).join("helium_proto.bin");
+    let protoc = std::env::var_os("PROTOC").unwrap_or_else(|| "protoc".into());
+    let status = Command::new(protoc)
+        .arg("--include_imports")
+        .arg(format!("--descriptor_set_out={}", out.display()))
+        .arg(format!("--proto_path={}", proto_dir.display()))
+        .args(&files)
+        .status()
+        .expect("failed to run protoc, install it or set PROTOC");
+
+    assert!(status.success(), "protoc failed with {status}");
+}
+
+// The .proto files live next to the helium-proto crate's sources, which cargo metadata locates
+// wherever the git dependency was checked out.
+fn helium_proto_dir() -> PathBuf {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let manifest = Path::new(&std::env::var_os("CARGO_MANIFEST_DIR").unwrap()).join("Cargo.toml");
+    let output = Command::new(cargo)
+        .args(["metadata", "--format-version", "1", "--manifest-path"])
+        .arg(&manifest)
+        .output()
+        .expect("failed to run cargo metadata");
+    assert!(
+        output.status.success(),
+        "cargo metadata failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let manifest_path = metadata["packages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|package| package["name"] == "helium-proto")
+        .and_then(|package| package["manifest_path"].as_str())
+        .expect("helium-proto not found in cargo metadata");
+
+    Path::new(manifest_path).parent().unwrap().join("src")
+}
+
+fn proto_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            proto_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "proto") {
+            files.push(path);
+        }
+    }
+}
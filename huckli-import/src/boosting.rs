@@ -1,8 +1,10 @@
+use anyhow::Context;
 use chrono::{DateTime, Utc};
-use helium_proto::BoostedHexUpdateV1;
+use helium_proto::{BoostedHexUpdateV1, services::poc_mobile::OracleBoostingReportV1};
 use huckli_import_derive::Import;
+use rust_decimal::Decimal;
 
-use crate::determine_timestamp;
+use crate::{determine_timestamp, optional_uuid};
 
 #[derive(Debug, Import)]
 #[import(s3decode(
@@ -11,19 +13,12 @@ use crate::determine_timestamp;
     prefix = "boosted_hex_update",
 ))]
 pub struct BoostedHexUpdate {
-    #[import(sql = "timestamptz")]
     timestamp: DateTime<Utc>,
-    #[import(sql = "uint64")]
     location: u64,
-    #[import(sql = "timestamptz")]
     start_ts: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_ts: DateTime<Utc>,
-    #[import(sql = "uint32")]
     period_length: u32,
-    #[import(sql = "uint32")]
-    multiplier: u32,
-    #[import(sql = "uint32")]
+    multiplier: Option<u32>,
     version: u32,
 }
 
@@ -36,8 +31,98 @@ impl From<BoostedHexUpdateV1> for BoostedHexUpdate {
             start_ts: determine_timestamp(update.start_ts),
             end_ts: determine_timestamp(update.end_ts),
             period_length: update.period_length,
-            multiplier: update.multipliers.first().unwrap_or(&0).to_owned(),
+            multiplier: update.multipliers.first().copied(),
             version: update.version,
         }
     }
 }
+
+#[derive(Debug, Import)]
+#[import(table_name = "oracle_boosting_reports")]
+#[import(s3decode(
+    proto = OracleBoostingReportV1,
+    bucket = "helium-mainnet-mobile-verified",
+    prefix = "oracle_boosting_report",
+))]
+pub struct OracleBoostingReport {
+    coverage_object: Option<String>,
+    timestamp: DateTime<Utc>,
+    #[import(children, foreign_key = "coverage_object")]
+    assignments: Vec<OracleBoostingHexAssignment>,
+}
+
+impl TryFrom<OracleBoostingReportV1> for OracleBoostingReport {
+    type Error = anyhow::Error;
+
+    fn try_from(value: OracleBoostingReportV1) -> anyhow::Result<Self> {
+        let coverage_object = optional_uuid(&value.coverage_object)?;
+        let timestamp = determine_timestamp(value.timestamp);
+
+        Ok(Self {
+            coverage_object,
+            timestamp,
+            assignments: value
+                .assignments
+                .iter()
+                .map(|a| {
+                    Ok(OracleBoostingHexAssignment {
+                        timestamp,
+                        location: u64::from_str_radix(&a.location, 16).with_context(|| {
+                            format!("invalid assignment location {:?}", a.location)
+                        })?,
+                        urbanized: a.urbanized().as_str_name().to_string(),
+                        footfall: a.footfall().as_str_name().to_string(),
+                        landtype: a.landtype().as_str_name().to_string(),
+                        service_provider_override: a
+                            .service_provider_override()
+                            .as_str_name()
+                            .to_string(),
+                        // the verifier writes the multiplier scaled by 1000
+                        assignment_multiplier: Decimal::new(a.assignment_multiplier.into(), 3),
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+}
+
+#[derive(Debug, Import)]
+#[import(child, table_name = "oracle_boosting_assignments")]
+pub struct OracleBoostingHexAssignment {
+    timestamp: DateTime<Utc>,
+    location: u64,
+    urbanized: String,
+    footfall: String,
+    landtype: String,
+    service_provider_override: String,
+    assignment_multiplier: Decimal,
+}
+
+#[cfg(test)]
+mod tests {
+    use helium_proto::services::poc_mobile::OracleBoostingHexAssignment as Assignment;
+
+    use super::*;
+
+    fn report(location: &str) -> OracleBoostingReportV1 {
+        OracleBoostingReportV1 {
+            assignments: vec![Assignment {
+                location: location.to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_assignment_locations_as_hex() {
+        let report = OracleBoostingReport::try_from(report("8c2681a3064edff")).unwrap();
+        assert_eq!(report.assignments[0].location, 0x8c2681a3064edff);
+    }
+
+    #[test]
+    fn rejects_malformed_assignment_locations() {
+        assert!(OracleBoostingReport::try_from(report("")).is_err());
+        assert!(OracleBoostingReport::try_from(report("not hex")).is_err());
+    }
+}
@@ -1,111 +1,120 @@
+use anyhow::Context;
 use chrono::{DateTime, Utc};
-use helium_proto::services::poc_mobile::{CoverageObjectV1, coverage_object_req_v1};
+use helium_proto::services::poc_mobile::{
+    CoverageObjectIngestReportV1, CoverageObjectReqV1, CoverageObjectV1, coverage_object_req_v1,
+};
 use huckli_import_derive::Import;
 
 use crate::{PublicKeyBinary, determine_timestamp};
 
-#[derive(Debug)]
-pub struct CoverageObjectProto {
-    object: CoverageObject,
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = CoverageObjectV1,
+    bucket = "helium-mainnet-mobile-verified",
+    prefix = "coverage_object",
+))]
+pub struct CoverageObject {
+    radio_key: String,
+    radio_type: String,
+    uuid: String,
+    coverage_claim_time: DateTime<Utc>,
+    indoor: bool,
+    #[import(children, foreign_key = "uuid")]
     locations: Vec<CoverageLocation>,
 }
 
-impl crate::DbTable for CoverageObjectProto {
-    fn create_table(db: &huckli_db::Db) -> anyhow::Result<()> {
-        CoverageObject::create_table(db)?;
-        CoverageLocation::create_table(db)?;
+impl TryFrom<CoverageObjectV1> for CoverageObject {
+    type Error = anyhow::Error;
 
-        Ok(())
-    }
+    fn try_from(value: CoverageObjectV1) -> anyhow::Result<Self> {
+        let req = value
+            .coverage_object
+            .as_ref()
+            .context("missing coverage object")?;
 
-    fn save(db: &huckli_db::Db, data: Vec<Self>) -> anyhow::Result<()> {
-        let mut objects = Vec::new();
-        let mut locations = Vec::new();
+        let (radio_key, radio_type) = radio_key_and_type(req)?;
 
-        for mut p in data {
-            objects.push(p.object);
-            locations.append(&mut p.locations);
-        }
-
-        CoverageObject::save(db, objects)?;
-        CoverageLocation::save(db, locations)?;
-
-        Ok(())
+        Ok(Self {
+            radio_key,
+            radio_type,
+            uuid: uuid::Uuid::from_slice(&req.uuid)?.to_string(),
+            coverage_claim_time: determine_timestamp(req.coverage_claim_time),
+            indoor: req.indoor,
+            locations: locations(req),
+        })
     }
 }
 
-impl CoverageObjectProto {
-    pub async fn get_and_persist(
-        db: &huckli_db::Db,
-        s3: &huckli_s3::S3,
-        selection: &crate::FileSelectionArgs,
-    ) -> anyhow::Result<()> {
-        crate::get_and_persist::<CoverageObjectV1, CoverageObjectProto>(
-            db,
-            s3,
-            "helium-mainnet-mobile-verified",
-            "coverage_object",
-            selection,
-        )
-        .await
+fn radio_key_and_type(req: &CoverageObjectReqV1) -> anyhow::Result<(String, String)> {
+    match req.key_type.as_ref() {
+        Some(coverage_object_req_v1::KeyType::HotspotKey(hk)) => Ok((
+            PublicKeyBinary::from(hk.clone()).to_string(),
+            "wifi".to_string(),
+        )),
+        Some(coverage_object_req_v1::KeyType::CbsdId(cbsd_id)) => {
+            Ok((cbsd_id.to_owned(), "cbrs".to_string()))
+        }
+        _ => anyhow::bail!("coverage object has no supported key type"),
     }
 }
 
-impl From<CoverageObjectV1> for CoverageObjectProto {
-    fn from(value: CoverageObjectV1) -> Self {
-        let req = value.coverage_object.as_ref().unwrap();
-
-        let (radio_key, radio_type) = match req.key_type.as_ref() {
-            Some(coverage_object_req_v1::KeyType::HotspotKey(hk)) => (
-                PublicKeyBinary::from(hk.clone()).to_string(),
-                "wifi".to_string(),
-            ),
-            Some(coverage_object_req_v1::KeyType::CbsdId(cbsd_id)) => {
-                (cbsd_id.to_owned(), "cbrs".to_string())
-            }
-            _ => panic!("invalid key type"),
-        };
-
-        let uuid = uuid::Uuid::from_slice(&req.uuid).unwrap().to_string();
+#[derive(Debug, Import)]
+#[import(child)]
+pub struct CoverageLocation {
+    location: String,
+    signal_level: String,
+    signal_power: i32,
+}
 
-        Self {
-            object: CoverageObject {
-                radio_key,
-                radio_type,
-                uuid: uuid.clone(),
-                coverage_claim_time: determine_timestamp(req.coverage_claim_time),
-                indoor: req.indoor,
-            },
-            locations: req
-                .coverage
-                .iter()
-                .map(|c| CoverageLocation {
-                    uuid: uuid.clone(),
-                    location: c.location.clone(),
-                    signal_level: c.signal_level().as_str_name().to_string(),
-                    signal_power: c.signal_power,
-                })
-                .collect(),
-        }
-    }
+fn locations(req: &CoverageObjectReqV1) -> Vec<CoverageLocation> {
+    req.coverage
+        .iter()
+        .map(|c| CoverageLocation {
+            location: c.location.clone(),
+            signal_level: c.signal_level().as_str_name().to_string(),
+            signal_power: c.signal_power,
+        })
+        .collect()
 }
 
 #[derive(Debug, Import)]
-pub struct CoverageObject {
+#[import(table_name = "coverage_object_ingest")]
+#[import(s3decode(
+    proto = CoverageObjectIngestReportV1,
+    bucket = "helium-mainnet-mobile-ingest",
+    prefix = "coverage_object_ingest_report",
+))]
+pub struct CoverageObjectIngest {
     radio_key: String,
     radio_type: String,
     uuid: String,
-    #[import(sql = "timestamptz")]
     coverage_claim_time: DateTime<Utc>,
-    #[import(sql = "bool")]
     indoor: bool,
+    received_timestamp: DateTime<Utc>,
+    #[import(
+        children,
+        foreign_key = "uuid",
+        table_name = "coverage_location_ingest"
+    )]
+    locations: Vec<CoverageLocation>,
 }
 
-#[derive(Debug, Import)]
-pub struct CoverageLocation {
-    uuid: String,
-    location: String,
-    signal_level: String,
-    #[import(sql = "int32")]
-    signal_power: i32,
+impl TryFrom<CoverageObjectIngestReportV1> for CoverageObjectIngest {
+    type Error = anyhow::Error;
+
+    fn try_from(value: CoverageObjectIngestReportV1) -> anyhow::Result<Self> {
+        let req = value.report.as_ref().context("missing report")?;
+
+        let (radio_key, radio_type) = radio_key_and_type(req)?;
+
+        Ok(Self {
+            radio_key,
+            radio_type,
+            uuid: uuid::Uuid::from_slice(&req.uuid)?.to_string(),
+            coverage_claim_time: determine_timestamp(req.coverage_claim_time),
+            indoor: req.indoor,
+            received_timestamp: determine_timestamp(value.received_timestamp),
+            locations: locations(req),
+        })
+    }
 }
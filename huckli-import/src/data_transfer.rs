@@ -1,7 +1,10 @@
 use chrono::{DateTime, Utc};
 use helium_proto::services::{
     packet_verifier::ValidDataTransferSession,
-    poc_mobile::{DataTransferSessionIngestReportV1, VerifiedDataTransferIngestReportV1},
+    poc_mobile::{
+        DataTransferSessionIngestReportV1, InvalidDataTransferIngestReportV1,
+        VerifiedDataTransferIngestReportV1,
+    },
 };
 use huckli_import_derive::Import;
 
@@ -12,21 +15,18 @@ use crate::{PublicKeyBinary, determine_timestamp};
     proto = DataTransferSessionIngestReportV1,
     bucket = "helium-mainnet-mobile-ingest",
     prefix = "data_transfer_session_ingest_report",
+    name = "data-transfer-ingest",
 ))]
 pub struct DataTransferIngestReport {
     hotspot_key: String,
-    #[import(sql = "uint64")]
     upload_bytes: u64,
-    #[import(sql = "uint64")]
     download_bytes: u64,
-    #[import(sql = "uint64")]
     rewardable_bytes: u64,
     technology: String,
+    #[import(key)]
     event_id: String,
     payer: String,
-    #[import(sql = "timestamptz")]
     timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     received_timestamp: DateTime<Utc>,
     carrier_id: String,
 }
@@ -56,23 +56,19 @@ impl From<DataTransferSessionIngestReportV1> for DataTransferIngestReport {
     proto = VerifiedDataTransferIngestReportV1,
     bucket = "helium-mainnet-mobile-packet-verifier",
     prefix = "verified_data_transfer_session",
+    name = "verified-data-transfer",
 ))]
 pub struct VerifiedDataTransferIngestReport {
     hotspot_key: String,
-    #[import(sql = "uint64")]
     upload_bytes: u64,
-    #[import(sql = "uint64")]
     download_bytes: u64,
-    #[import(sql = "uint64")]
     rewardable_bytes: u64,
     technology: String,
+    #[import(key)]
     event_id: String,
     payer: String,
-    #[import(sql = "timestamptz")]
     timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     received_timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     verified_timestamp: DateTime<Utc>,
     status: String,
     carrier: String,
@@ -101,6 +97,52 @@ impl From<VerifiedDataTransferIngestReportV1> for VerifiedDataTransferIngestRepo
     }
 }
 
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = InvalidDataTransferIngestReportV1,
+    bucket = "helium-mainnet-mobile-packet-verifier",
+    prefix = "invalid_data_transfer_session",
+    name = "invalid-data-transfer",
+))]
+pub struct InvalidDataTransferIngestReport {
+    hotspot_key: String,
+    upload_bytes: u64,
+    download_bytes: u64,
+    rewardable_bytes: u64,
+    technology: String,
+    #[import(key)]
+    event_id: String,
+    payer: String,
+    timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    carrier_id: String,
+    reason: String,
+    invalid_timestamp: DateTime<Utc>,
+}
+
+impl From<InvalidDataTransferIngestReportV1> for InvalidDataTransferIngestReport {
+    fn from(value: InvalidDataTransferIngestReportV1) -> Self {
+        let ingest = value.report.as_ref().unwrap();
+        let req = ingest.report.as_ref().unwrap();
+        let event = req.data_transfer_usage.as_ref().unwrap();
+
+        Self {
+            hotspot_key: PublicKeyBinary::from(event.pub_key.clone()).to_string(),
+            upload_bytes: event.upload_bytes,
+            download_bytes: event.download_bytes,
+            rewardable_bytes: req.rewardable_bytes,
+            technology: event.radio_access_technology().as_str_name().to_string(),
+            event_id: event.event_id.clone(),
+            payer: PublicKeyBinary::from(event.payer.clone()).to_string(),
+            timestamp: determine_timestamp(event.timestamp),
+            received_timestamp: determine_timestamp(ingest.received_timestamp),
+            carrier_id: req.carrier_id_v2().as_str_name().to_string(),
+            reason: value.reason().as_str_name().to_string(),
+            invalid_timestamp: determine_timestamp(value.timestamp),
+        }
+    }
+}
+
 #[derive(Debug, Import)]
 #[import(s3decode(
     proto = ValidDataTransferSession,
@@ -109,20 +151,13 @@ impl From<VerifiedDataTransferIngestReportV1> for VerifiedDataTransferIngestRepo
 ))]
 pub struct DataTransferBurn {
     hotspot_key: String,
-    #[import(sql = "uint64")]
     upload_bytes: u64,
-    #[import(sql = "uint64")]
     download_bytes: u64,
-    #[import(sql = "uint64")]
     num_dcs: u64,
     payer: String,
-    #[import(sql = "timestamptz")]
     first_timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     last_timestamp: DateTime<Utc>,
-    #[import(sql = "uint64")]
     rewardable_bytes: u64,
-    #[import(sql = "timestamptz")]
     burn_timestamp: DateTime<Utc>,
 }
 
@@ -12,7 +12,6 @@ use crate::{PublicKeyBinary, determine_timestamp};
 ))]
 pub struct EnabledCarriersInfo {
     hotspot_key: String,
-    #[import(sql = "json")]
     enabled_carriers: serde_json::Value,
     sampled_carriers: serde_json::Value,
     firmware_version: String,
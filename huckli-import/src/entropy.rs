@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use helium_proto::EntropyReportV1;
+use huckli_import_derive::Import;
+
+use crate::determine_timestamp;
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = EntropyReportV1,
+    bucket = "helium-mainnet-entropy",
+    prefix = "entropy_report",
+))]
+pub struct EntropyReport {
+    data: Vec<u8>,
+    timestamp: DateTime<Utc>,
+    version: u32,
+}
+
+impl From<EntropyReportV1> for EntropyReport {
+    fn from(value: EntropyReportV1) -> Self {
+        Self {
+            data: value.data,
+            timestamp: determine_timestamp(value.timestamp),
+            version: value.version,
+        }
+    }
+}
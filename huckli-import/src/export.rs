@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use crate::FileType;
+
+#[derive(Debug, clap::Args)]
+pub struct ExportArgs {
+    /// Table to export, may be given multiple times
+    #[arg(long, required_unless_present = "file_type")]
+    table: Vec<String>,
+    /// Export every table written by this file type
+    #[arg(long, value_parser = crate::file_type_parser())]
+    file_type: Option<&'static FileType>,
+    #[arg(long, default_value = "parquet")]
+    format: Format,
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Timestamp column to partition by day, defaults to the source file timestamp
+    #[arg(long)]
+    partition_column: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum Format {
+    Parquet,
+    Csv,
+}
+
+impl From<Format> for huckli_db::ExportFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Parquet => huckli_db::ExportFormat::Parquet,
+            Format::Csv => huckli_db::ExportFormat::Csv,
+        }
+    }
+}
+
+pub fn run(args: &ExportArgs, db: &huckli_db::Db) -> anyhow::Result<()> {
+    let mut tables = args.table.clone();
+    if let Some(file_type) = &args.file_type {
+        tables.extend((file_type.table_names)().into_iter().map(str::to_string));
+    }
+
+    for table in tables {
+        let rows = db.export_table(
+            &table,
+            args.format.into(),
+            &args.output,
+            args.partition_column.as_deref(),
+        )?;
+
+        tracing::info!(table, rows, "exported");
+    }
+
+    Ok(())
+}
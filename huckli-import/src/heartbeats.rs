@@ -1,51 +1,50 @@
+use anyhow::Context;
 use chrono::{DateTime, Utc};
 use helium_proto::services::poc_mobile::{Heartbeat, WifiHeartbeatIngestReportV1};
 use huckli_import_derive::Import;
-use uuid::Uuid;
 
-use crate::{PublicKeyBinary, determine_timestamp};
+use crate::{PublicKeyBinary, determine_optional_timestamp, determine_timestamp, optional_uuid};
 
 #[derive(Debug, Import)]
 #[import(s3decode(
     proto = Heartbeat,
     bucket = "helium-mainnet-mobile-verified",
-    prefix = "validated_heartbeat"
+    prefix = "validated_heartbeat",
+    name = "validated-heartbeat"
 ))]
 pub struct VerifiedWifiHeartbeat {
+    #[import(key)]
     hotspot_key: String,
-    #[import(sql = "timestamptz")]
+    #[import(key)]
     timestamp: DateTime<Utc>,
     validity: String,
-    #[import(sql = "double")]
     lat: f64,
-    #[import(sql = "double")]
     lon: f64,
-    coverage_object: String,
-    #[import(sql = "timestamptz")]
-    location_validation_timestamp: DateTime<Utc>,
-    #[import(sql = "uint64")]
+    coverage_object: Option<String>,
+    location_validation_timestamp: Option<DateTime<Utc>>,
     distance_to_asserted: u64,
-    #[import(sql = "uint32")]
     location_trust_score_multiplier: u32,
     location_source: String,
 }
 
-impl From<Heartbeat> for VerifiedWifiHeartbeat {
-    fn from(value: Heartbeat) -> Self {
-        Self {
+impl TryFrom<Heartbeat> for VerifiedWifiHeartbeat {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Heartbeat) -> anyhow::Result<Self> {
+        Ok(Self {
             hotspot_key: PublicKeyBinary::from(value.pub_key.clone()).to_string(),
             timestamp: determine_timestamp(value.timestamp),
             validity: value.validity().as_str_name().to_string(),
             lat: value.lat,
             lon: value.lon,
-            coverage_object: Uuid::from_slice(&value.coverage_object)
-                .unwrap()
-                .to_string(),
-            location_validation_timestamp: determine_timestamp(value.location_validation_timestamp),
+            coverage_object: optional_uuid(&value.coverage_object)?,
+            location_validation_timestamp: determine_optional_timestamp(
+                value.location_validation_timestamp,
+            ),
             distance_to_asserted: value.distance_to_asserted,
             location_trust_score_multiplier: value.location_trust_score_multiplier,
             location_source: value.location_source().as_str_name().to_string(),
-        }
+        })
     }
 }
 
@@ -53,45 +52,42 @@ impl From<Heartbeat> for VerifiedWifiHeartbeat {
 #[import(s3decode(
     proto = WifiHeartbeatIngestReportV1,
     bucket = "helium-mainnet-mobile-ingest",
-    prefix = "wifi_heartbeat_report"
+    prefix = "wifi_heartbeat_report",
+    name = "wifi-heartbeat-ingest"
 ))]
 pub struct WifiHeartbeatIngestReport {
+    #[import(key)]
     hotspot_key: String,
-    #[import(sql = "timestamptz")]
+    #[import(key)]
     timestamp: DateTime<Utc>,
-    #[import(sql = "double")]
     lat: f64,
-    #[import(sql = "double")]
     lon: f64,
-    coverage_object: String,
-    #[import(sql = "timestamptz")]
+    coverage_object: Option<String>,
     received_timestamp: DateTime<Utc>,
-    #[import(sql = "bool")]
     operation_mode: bool,
-    #[import(sql = "timestamptz")]
-    location_validation_timestamp: DateTime<Utc>,
+    location_validation_timestamp: Option<DateTime<Utc>>,
     location_source: String,
 }
 
-impl From<WifiHeartbeatIngestReportV1> for WifiHeartbeatIngestReport {
-    fn from(value: WifiHeartbeatIngestReportV1) -> Self {
+impl TryFrom<WifiHeartbeatIngestReportV1> for WifiHeartbeatIngestReport {
+    type Error = anyhow::Error;
+
+    fn try_from(value: WifiHeartbeatIngestReportV1) -> anyhow::Result<Self> {
         let report = value
             .report
-            .expect("WifiHeartbeatIngestReportV1 should have a report");
-        Self {
+            .context("WifiHeartbeatIngestReportV1 should have a report")?;
+        Ok(Self {
             hotspot_key: PublicKeyBinary::from(report.pub_key.clone()).to_string(),
             timestamp: determine_timestamp(report.timestamp),
             lat: report.lat,
             lon: report.lon,
-            coverage_object: Uuid::from_slice(&report.coverage_object)
-                .unwrap()
-                .to_string(),
+            coverage_object: optional_uuid(&report.coverage_object)?,
             received_timestamp: determine_timestamp(value.received_timestamp),
             operation_mode: report.operation_mode,
-            location_validation_timestamp: determine_timestamp(
+            location_validation_timestamp: determine_optional_timestamp(
                 report.location_validation_timestamp,
             ),
             location_source: report.location_source().as_str_name().to_string(),
-        }
+        })
     }
 }
@@ -0,0 +1,140 @@
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use helium_proto::services::poc_mobile::{
+    BanIngestReportV1, BanReqV1, VerifiedBanIngestReportV1, ban_req_v1::BanAction,
+};
+use huckli_import_derive::Import;
+
+use crate::{PublicKeyBinary, determine_optional_timestamp, determine_timestamp, non_empty};
+
+struct BanFields {
+    action: String,
+    ban_type: Option<String>,
+    reason: Option<String>,
+    notes: Option<String>,
+    expiration_timestamp: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<&BanReqV1> for BanFields {
+    type Error = anyhow::Error;
+
+    fn try_from(req: &BanReqV1) -> anyhow::Result<Self> {
+        match req.ban_action.as_ref() {
+            Some(BanAction::Ban(details)) => Ok(Self {
+                action: "ban".to_string(),
+                ban_type: Some(details.ban_type().as_str_name().to_string()),
+                reason: Some(details.reason().as_str_name().to_string()),
+                notes: non_empty(&details.notes),
+                // a zero expiration means the ban is permanent
+                expiration_timestamp: determine_optional_timestamp(details.expiration_timestamp_ms),
+            }),
+            Some(BanAction::Unban(details)) => Ok(Self {
+                action: "unban".to_string(),
+                ban_type: None,
+                reason: None,
+                notes: non_empty(&details.notes),
+                expiration_timestamp: None,
+            }),
+            None => anyhow::bail!("ban report has no ban action"),
+        }
+    }
+}
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = BanIngestReportV1,
+    bucket = "helium-mainnet-mobile-ingest",
+    prefix = "mobile_ban_report",
+))]
+pub struct HotspotBanIngest {
+    hotspot_key: String,
+    action: String,
+    ban_type: Option<String>,
+    reason: Option<String>,
+    notes: Option<String>,
+    expiration_timestamp: Option<DateTime<Utc>>,
+    ban_key: String,
+    timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+}
+
+impl TryFrom<BanIngestReportV1> for HotspotBanIngest {
+    type Error = anyhow::Error;
+
+    fn try_from(value: BanIngestReportV1) -> anyhow::Result<Self> {
+        let req = value.report.as_ref().context("missing report")?;
+        let ban = BanFields::try_from(req)?;
+
+        Ok(Self {
+            hotspot_key: PublicKeyBinary::from(req.hotspot_pubkey.clone()).to_string(),
+            action: ban.action,
+            ban_type: ban.ban_type,
+            reason: ban.reason,
+            notes: ban.notes,
+            expiration_timestamp: ban.expiration_timestamp,
+            ban_key: PublicKeyBinary::from(req.ban_pubkey.clone()).to_string(),
+            timestamp: determine_timestamp(req.timestamp_ms),
+            received_timestamp: determine_timestamp(value.received_timestamp_ms),
+        })
+    }
+}
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = VerifiedBanIngestReportV1,
+    bucket = "helium-mainnet-mobile-verified",
+    prefix = "verified_mobile_ban_report",
+))]
+pub struct VerifiedHotspotBan {
+    hotspot_key: String,
+    action: String,
+    ban_type: Option<String>,
+    reason: Option<String>,
+    notes: Option<String>,
+    expiration_timestamp: Option<DateTime<Utc>>,
+    ban_key: String,
+    timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    verified_timestamp: DateTime<Utc>,
+    status: String,
+}
+
+impl TryFrom<VerifiedBanIngestReportV1> for VerifiedHotspotBan {
+    type Error = anyhow::Error;
+
+    fn try_from(value: VerifiedBanIngestReportV1) -> anyhow::Result<Self> {
+        let ingest = value.report.as_ref().context("missing ingest report")?;
+        let req = ingest.report.as_ref().context("missing report")?;
+        let ban = BanFields::try_from(req)?;
+
+        Ok(Self {
+            hotspot_key: PublicKeyBinary::from(req.hotspot_pubkey.clone()).to_string(),
+            action: ban.action,
+            ban_type: ban.ban_type,
+            reason: ban.reason,
+            notes: ban.notes,
+            expiration_timestamp: ban.expiration_timestamp,
+            ban_key: PublicKeyBinary::from(req.ban_pubkey.clone()).to_string(),
+            timestamp: determine_timestamp(req.timestamp_ms),
+            received_timestamp: determine_timestamp(ingest.received_timestamp_ms),
+            verified_timestamp: determine_timestamp(value.verified_timestamp_ms),
+            status: value.status().as_str_name().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_reports_without_a_ban_action() {
+        let report = BanIngestReportV1 {
+            report: Some(BanReqV1::default()),
+            ..Default::default()
+        };
+
+        assert!(HotspotBanIngest::try_from(report).is_err());
+        assert!(HotspotBanIngest::try_from(BanIngestReportV1::default()).is_err());
+    }
+}
@@ -1,10 +1,16 @@
 use chrono::{DateTime, Utc};
-use helium_proto::services::poc_lora;
+use helium_proto::services::poc_lora::{self, IotRewardShare};
 use huckli_import_derive::Import;
 
 use crate::{PublicKeyBinary, determine_timestamp};
 
-#[derive(Debug)]
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = IotRewardShare,
+    bucket = "helium-mainnet-iot-verified-rewards",
+    prefix = "iot_network_reward_shares_v1",
+    name = "iot-rewards",
+))]
 pub enum IotReward {
     Gateway(IotGatewayReward),
     Operational(IotOperationalReward),
@@ -12,8 +18,8 @@ pub enum IotReward {
     Deprecated,
 }
 
-impl From<poc_lora::IotRewardShare> for IotReward {
-    fn from(value: poc_lora::IotRewardShare) -> Self {
+impl From<IotRewardShare> for IotReward {
+    fn from(value: IotRewardShare) -> Self {
         let start = determine_timestamp(value.start_period);
         let end = determine_timestamp(value.end_period);
 
@@ -32,59 +38,6 @@ impl From<poc_lora::IotRewardShare> for IotReward {
     }
 }
 
-impl crate::DbTable for IotReward {
-    fn create_table(db: &huckli_db::Db) -> anyhow::Result<()> {
-        IotGatewayReward::create_table(db)?;
-        IotOperationalReward::create_table(db)?;
-        IotUnallocatedReward::create_table(db)?;
-        Ok(())
-    }
-
-    fn save(db: &huckli_db::Db, data: Vec<Self>) -> anyhow::Result<()> {
-        let mut gateway_rewards = Vec::new();
-        let mut operational_rewards = Vec::new();
-        let mut unallocated_rewards = Vec::new();
-
-        for iot_reward in data {
-            match iot_reward {
-                IotReward::Gateway(gateway) => {
-                    gateway_rewards.push(gateway);
-                }
-                IotReward::Operational(operational) => {
-                    operational_rewards.push(operational);
-                }
-                IotReward::Unallocated(unallocated) => {
-                    unallocated_rewards.push(unallocated);
-                }
-                _ => (),
-            }
-        }
-
-        IotGatewayReward::save(db, gateway_rewards)?;
-        IotOperationalReward::save(db, operational_rewards)?;
-        IotUnallocatedReward::save(db, unallocated_rewards)?;
-
-        Ok(())
-    }
-}
-
-impl IotReward {
-    pub async fn get_and_persist(
-        db: &huckli_db::Db,
-        s3: &huckli_s3::S3,
-        selection: &crate::FileSelectionArgs,
-    ) -> anyhow::Result<()> {
-        crate::get_and_persist::<poc_lora::IotRewardShare, IotReward>(
-            db,
-            s3,
-            "helium-mainnet-iot-verified-rewards",
-            "iot_network_reward_shares_v1",
-            selection,
-        )
-        .await
-    }
-}
-
 trait ToIotReward {
     fn to_iot_reward(self, start: DateTime<Utc>, end: DateTime<Utc>) -> IotReward;
 }
@@ -92,16 +45,11 @@ trait ToIotReward {
 #[derive(Debug, Import)]
 #[import(table_name = "iot_gateway_rewards")]
 pub struct IotGatewayReward {
-    #[import(sql = "timestamptz")]
     start_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_period: DateTime<Utc>,
     hotspot_key: String,
-    #[import(sql = "uint64")]
     beacon_amount: u64,
-    #[import(sql = "uint64")]
     witness_amount: u64,
-    #[import(sql = "uint64")]
     dc_transfer_amount: u64,
 }
 
@@ -121,11 +69,8 @@ impl ToIotReward for poc_lora::GatewayReward {
 #[derive(Debug, Import)]
 #[import(table_name = "iot_operational_rewards")]
 pub struct IotOperationalReward {
-    #[import(sql = "timestamptz")]
     start_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_period: DateTime<Utc>,
-    #[import(sql = "uint64")]
     amount: u64,
 }
 
@@ -142,12 +87,9 @@ impl ToIotReward for poc_lora::OperationalReward {
 #[derive(Debug, Import)]
 #[import(table_name = "iot_unallocated_rewards")]
 pub struct IotUnallocatedReward {
-    #[import(sql = "timestamptz")]
     start_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_period: DateTime<Utc>,
     reward_type: String,
-    #[import(sql = "uint64")]
     amount: u64,
 }
 
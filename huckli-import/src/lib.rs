@@ -2,10 +2,19 @@ pub mod boosting;
 pub mod coverage;
 pub mod data_transfer;
 pub mod enabled_carriers_info;
+pub mod entropy;
+pub mod export;
 pub mod heartbeats;
+pub mod hotspot_ban;
 pub mod iot_rewards;
 pub mod mobile_rewards;
+pub mod packet_report;
+pub mod price;
+pub mod promotion_rewards;
+pub mod radio_location_estimates;
 pub mod radio_thresholds;
+pub mod raw;
+pub mod seniority;
 pub mod sp_banned_radio;
 pub mod subscribers;
 pub mod unique_connections;
@@ -16,8 +25,10 @@ pub mod verified_speedtest;
 use std::{cell::RefCell, str::FromStr};
 
 use anyhow::Context;
+use bytes::Bytes;
 use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
-use futures::{StreamExt, TryStreamExt};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use futures::{StreamExt, future::LocalBoxFuture};
 use rust_decimal::Decimal;
 
 thread_local! {
@@ -36,104 +47,65 @@ pub fn clear_file_source() {
     FILE_SOURCE.with(|fs| *fs.borrow_mut() = None);
 }
 
+/// A file type that can be imported, registered by `#[derive(Import)]` on types with `s3decode`.
+#[derive(Debug)]
+pub struct FileType {
+    pub name: &'static str,
+    pub bucket: &'static str,
+    pub prefix: &'static str,
+    pub proto: &'static str,
+    pub table_names: fn() -> Vec<&'static str>,
+    pub get_and_persist: GetAndPersist,
+}
+
+pub type GetAndPersist = for<'a> fn(
+    &'a huckli_db::Db,
+    &'a huckli_s3::S3,
+    &'a FileSelectionArgs,
+) -> LocalBoxFuture<'a, anyhow::Result<()>>;
+
+inventory::collect!(FileType);
+
+pub fn file_types() -> Vec<&'static FileType> {
+    let mut file_types = inventory::iter::<FileType>.into_iter().collect::<Vec<_>>();
+    file_types.sort_by_key(|file_type| file_type.name);
+    file_types
+}
+
+// Lets clap offer the registered names as choices and parse them into their `FileType`.
+pub fn file_type_parser() -> impl TypedValueParser<Value = &'static FileType> {
+    let file_types = file_types();
+    let names = file_types
+        .iter()
+        .map(|file_type| file_type.name)
+        .collect::<Vec<_>>();
+
+    PossibleValuesParser::new(names).map(move |name| {
+        file_types
+            .iter()
+            .copied()
+            .find(|file_type| file_type.name == name)
+            .expect("possible values come from the registry")
+    })
+}
+
 pub async fn run(
-    file_type: SupportedFileTypes,
+    file_type: &FileType,
     db: &huckli_db::Db,
     s3: &huckli_s3::S3,
     selection: &FileSelectionArgs,
 ) -> anyhow::Result<()> {
-    match file_type {
-        SupportedFileTypes::BoostedHexUpdate => {
-            boosting::BoostedHexUpdate::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::CoverageObject => {
-            coverage::CoverageObjectProto::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::DataTransferBurn => {
-            data_transfer::DataTransferBurn::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::DataTransferIngest => {
-            data_transfer::DataTransferIngestReport::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::IotRewards => {
-            iot_rewards::IotReward::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::MobileRewards => {
-            mobile_rewards::MobileReward::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::MobileRewardManifest => {
-            mobile_rewards::MobileRewardManifest::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::RadioUsageStats => {
-            usage::RadioUsageStats::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::RadioUsageStatsV2 => {
-            usage_v2::RadioUsageStatsV2::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::SubscriberMappingActivityIngest => {
-            subscribers::SubscriberMappingActivityIngest::get_and_persist(db, s3, selection)
-                .await?;
-        }
-        SupportedFileTypes::ValidatedHeartbeat => {
-            heartbeats::VerifiedWifiHeartbeat::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::WifiHeartbeatIngest => {
-            heartbeats::WifiHeartbeatIngestReport::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::VerifiedCdrVerification => {
-            sp_banned_radio::VerifiedCdrVerification::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::VerifiedDataTransfer => {
-            data_transfer::VerifiedDataTransferIngestReport::get_and_persist(db, s3, selection)
-                .await?;
-        }
-        SupportedFileTypes::VerifiedInvalidatedRadioThreshold => {
-            radio_thresholds::VerifiedInvalidatedRadioThreshold::get_and_persist(db, s3, selection)
-                .await?;
-        }
-        SupportedFileTypes::VerifiedRadioThreshold => {
-            radio_thresholds::VerifiedRadioThreshold::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::VerifiedSubscriberMappingActivity => {
-            subscribers::VerifiedSubscriberMappingActivity::get_and_persist(db, s3, selection)
-                .await?;
-        }
-        SupportedFileTypes::VerifiedSpeedtest => {
-            verified_speedtest::VerifiedSpeedtestReport::get_and_persist(db, s3, selection).await?;
-        }
-        SupportedFileTypes::VerifiedUniqueConnections => {
-            unique_connections::VerifiedUniqueConnections::get_and_persist(db, s3, selection)
-                .await?;
-        }
-        SupportedFileTypes::EnabledCarriersInfo => {
-            enabled_carriers_info::EnabledCarriersInfo::get_and_persist(db, s3, selection).await?;
-        }
-    }
-    Ok(())
+    (file_type.get_and_persist)(db, s3, selection).await
 }
 
-#[derive(Debug, Clone, clap::ValueEnum)]
-pub enum SupportedFileTypes {
-    BoostedHexUpdate,
-    CoverageObject,
-    DataTransferBurn,
-    DataTransferIngest,
-    IotRewards,
-    MobileRewards,
-    MobileRewardManifest,
-    RadioUsageStats,
-    RadioUsageStatsV2,
-    SubscriberMappingActivityIngest,
-    ValidatedHeartbeat,
-    VerifiedCdrVerification,
-    VerifiedDataTransfer,
-    WifiHeartbeatIngest,
-    VerifiedInvalidatedRadioThreshold,
-    VerifiedSubscriberMappingActivity,
-    VerifiedRadioThreshold,
-    VerifiedSpeedtest,
-    VerifiedUniqueConnections,
-    EnabledCarriersInfo,
+pub fn list_types() {
+    for file_type in file_types() {
+        println!("{}", file_type.name);
+        println!("  bucket: {}", file_type.bucket);
+        println!("  prefix: {}", file_type.prefix);
+        println!("  proto:  {}", file_type.proto);
+        println!("  tables: {}", (file_type.table_names)().join(", "));
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -174,29 +146,57 @@ pub fn determine_timestamp(timestamp: u64) -> DateTime<Utc> {
     }
 }
 
+// Proto timestamps are zero when not set.
+pub fn determine_optional_timestamp(timestamp: u64) -> Option<DateTime<Utc>> {
+    (timestamp > 0).then(|| determine_timestamp(timestamp))
+}
+
+// Proto bytes fields are empty when not set.
+pub fn optional_uuid(bytes: &[u8]) -> anyhow::Result<Option<String>> {
+    if bytes.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(uuid::Uuid::from_slice(bytes)?.to_string()))
+}
+
+// Proto string fields are empty when not set.
+pub fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
+
 pub trait DbTable: Sized {
     fn create_table(db: &huckli_db::Db) -> anyhow::Result<()>;
 
+    fn table_names() -> Vec<&'static str>;
+
     fn save(db: &huckli_db::Db, data: Vec<Self>) -> anyhow::Result<()>;
 }
 
-pub async fn get_and_persist<F, T>(
+// Imports every selected file under `prefix`, decoding each record with `decode`. Records that
+// fail to decode are logged and skipped.
+pub async fn get_and_persist<T, D>(
     db: &huckli_db::Db,
     s3: &huckli_s3::S3,
     bucket: &str,
     prefix: &str,
     selection: &FileSelectionArgs,
+    decode: D,
 ) -> anyhow::Result<()>
 where
-    F: prost::Message + Default,
-    T: From<F> + DbTable,
+    T: DbTable,
+    D: Fn(&huckli_s3::FileInfo, Bytes) -> anyhow::Result<T>,
 {
     T::create_table(db)?;
 
     let files = selection.get_files(s3, db, bucket, prefix).await?;
 
+    let decode = &decode;
     let mut stream = futures::stream::iter(files)
-        .map(|file| async { (file.clone(), get_and_decode::<F, T>(s3, bucket, file).await) })
+        .map(|file| async move {
+            let data = get_and_decode(s3, bucket, &file, decode).await;
+            (file, data)
+        })
         .buffered(10);
 
     while let Some((file, data)) = stream.next().await {
@@ -212,31 +212,42 @@ where
     Ok(())
 }
 
-pub async fn get_and_decode<F, T>(
+async fn get_and_decode<T, D>(
     s3: &huckli_s3::S3,
     bucket: &str,
-    file: huckli_s3::FileInfo,
+    file: &huckli_s3::FileInfo,
+    decode: &D,
 ) -> Vec<T>
 where
-    F: prost::Message + Default,
-    T: From<F>,
+    D: Fn(&huckli_s3::FileInfo, Bytes) -> anyhow::Result<T>,
 {
-    s3.stream_files(bucket, vec![file])
-        .then(|b| async move { F::decode(b) })
-        .and_then(|f| async move { Ok(T::from(f)) })
-        .filter_map(|result| async move {
-            match result {
+    s3.stream_files(bucket, vec![file.clone()])
+        .filter_map(|bytes| {
+            let result = match decode(file, bytes.freeze()) {
                 Ok(t) => Some(t),
-                Err(e) => {
-                    eprintln!("error in decoding record: {e}");
+                Err(err) => {
+                    tracing::warn!(file = %file.key, ?err, "error in decoding record");
                     None
                 }
-            }
+            };
+
+            futures::future::ready(result)
         })
         .collect()
         .await
 }
 
+// The decoder for types generated from a helium-proto message. Conversions that can fail
+// implement `TryFrom`, so a malformed record is skipped instead of aborting the import.
+pub fn decode_proto<F, T>(_file: &huckli_s3::FileInfo, bytes: Bytes) -> anyhow::Result<T>
+where
+    F: prost::Message + Default,
+    T: TryFrom<F>,
+    anyhow::Error: From<T::Error>,
+{
+    Ok(T::try_from(F::decode(bytes)?)?)
+}
+
 #[derive(Debug, clap::Args)]
 pub struct FileSelectionArgs {
     #[arg(long)]
@@ -304,10 +315,181 @@ impl FileSelectionArgs {
     }
 }
 
-fn from_proto_decimal(opt: Option<&helium_proto::Decimal>) -> f64 {
-    opt.ok_or_else(|| anyhow::anyhow!("decimal not present"))
-        .and_then(|d| Decimal::from_str(&d.value).map_err(anyhow::Error::from))
-        .unwrap_or_default()
-        .try_into()
-        .unwrap()
+fn from_proto_decimal(opt: Option<&helium_proto::Decimal>) -> Option<Decimal> {
+    let value = &opt?.value;
+    match Decimal::from_str(value) {
+        Ok(decimal) => Some(decimal),
+        Err(err) => {
+            tracing::warn!(
+                file = get_file_source().unwrap_or_default(),
+                %value,
+                ?err,
+                "invalid decimal, storing NULL"
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use huckli_import_derive::Import;
+
+    use super::*;
+
+    #[derive(Debug, Import)]
+    #[import(table_name = "test_parents")]
+    struct TestParent {
+        id: String,
+        name: Option<String>,
+        #[import(children, table_name = "test_parent_kids")]
+        kids: Vec<TestKid>,
+    }
+
+    #[derive(Debug, Import)]
+    #[import(child)]
+    struct TestKid {
+        value: u64,
+    }
+
+    #[derive(Debug, Import)]
+    #[import(table_name = "test_others")]
+    struct TestOther {
+        amount: i64,
+    }
+
+    #[derive(Debug, Import)]
+    enum TestRecord {
+        Parent(TestParent),
+        Other(TestOther),
+        Skipped,
+    }
+
+    // A file database, so that rows can be read back once the `Db` is dropped.
+    struct TempDb(std::path::PathBuf);
+
+    impl TempDb {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "huckli-import-{name}-{}.duckdb",
+                std::process::id()
+            ));
+            let _ = std::fs::remove_file(&path);
+            Self(path)
+        }
+
+        fn connect(&self) -> huckli_db::Db {
+            huckli_db::Db::connect(self.0.to_str().unwrap()).unwrap()
+        }
+
+        fn strings(&self, sql: &str) -> Vec<String> {
+            let connection = duckdb::Connection::open(&self.0).unwrap();
+            let mut statement = connection.prepare(sql).unwrap();
+            statement
+                .query_map([], |r| r.get(0))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+            let _ = std::fs::remove_file(self.0.with_extension("duckdb.wal"));
+        }
+    }
+
+    fn parent(id: &str, name: Option<&str>, kids: &[u64]) -> TestParent {
+        TestParent {
+            id: id.to_string(),
+            name: name.map(str::to_string),
+            kids: kids.iter().map(|&value| TestKid { value }).collect(),
+        }
+    }
+
+    #[test]
+    fn saves_children_with_their_parents_key() {
+        let temp = TempDb::new("children");
+        {
+            let db = temp.connect();
+            TestParent::create_table(&db).unwrap();
+            TestParent::save(
+                &db,
+                vec![
+                    parent("a", Some("first"), &[1, 2]),
+                    parent("b", None, &[]),
+                    parent("c", None, &[3]),
+                ],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            TestParent::table_names(),
+            ["test_parents", "test_parent_kids"]
+        );
+        assert_eq!(
+            temp.strings(
+                "SELECT column_name FROM information_schema.columns WHERE table_name = 'test_parent_kids' ORDER BY ordinal_position"
+            ),
+            ["id", "value", "file_source"]
+        );
+        assert_eq!(
+            temp.strings(
+                "SELECT id || ':' || COALESCE(name, 'null') FROM test_parents ORDER BY id"
+            ),
+            ["a:first", "b:null", "c:null"]
+        );
+        assert_eq!(
+            temp.strings(
+                "SELECT id || ':' || CAST(value AS VARCHAR) FROM test_parent_kids ORDER BY value"
+            ),
+            ["a:1", "a:2", "c:3"]
+        );
+    }
+
+    #[test]
+    fn routes_enum_variants_to_their_tables() {
+        let temp = TempDb::new("enum");
+        {
+            let db = temp.connect();
+            TestRecord::create_table(&db).unwrap();
+            TestRecord::save(
+                &db,
+                vec![
+                    TestRecord::Other(TestOther { amount: 1 }),
+                    TestRecord::Skipped,
+                    TestRecord::Parent(parent("a", None, &[7])),
+                    TestRecord::Other(TestOther { amount: 2 }),
+                    TestRecord::Skipped,
+                ],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            TestRecord::table_names(),
+            ["test_parents", "test_parent_kids", "test_others"]
+        );
+        assert_eq!(temp.strings("SELECT id FROM test_parents"), ["a"]);
+        assert_eq!(
+            temp.strings("SELECT id || ':' || CAST(value AS VARCHAR) FROM test_parent_kids"),
+            ["a:7"]
+        );
+        assert_eq!(
+            temp.strings("SELECT CAST(amount AS VARCHAR) FROM test_others ORDER BY amount"),
+            ["1", "2"]
+        );
+    }
+
+    #[test]
+    fn optional_uuid_is_none_when_unset_and_fails_when_malformed() {
+        assert_eq!(optional_uuid(&[]).unwrap(), None);
+        assert_eq!(
+            optional_uuid(&[0xab; 16]).unwrap().as_deref(),
+            Some("abababab-abab-abab-abab-abababababab")
+        );
+        assert!(optional_uuid(&[0xab; 15]).is_err());
+    }
 }
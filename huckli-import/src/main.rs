@@ -1,19 +1,33 @@
+use anyhow::Context;
 use clap::Parser;
-use huckli_import::{FileSelectionArgs, SupportedFileTypes};
+use huckli_import::{FileSelectionArgs, FileType};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[derive(Debug, clap::Parser)]
+#[command(subcommand_negates_reqs = true)]
 struct Args {
-    #[arg(short, long)]
-    db: String,
-    #[arg(long)]
-    file_type: SupportedFileTypes,
+    #[arg(short, long, required = true)]
+    db: Option<String>,
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[arg(long, required_unless_present = "message", value_parser = huckli_import::file_type_parser())]
+    file_type: Option<&'static FileType>,
+    #[command(flatten)]
+    raw: huckli_import::raw::RawArgs,
     #[command(flatten)]
     s3: huckli_s3::S3Args,
     #[command(flatten)]
     selection: FileSelectionArgs,
 }
 
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Export imported tables to partitioned Parquet or CSV files
+    Export(huckli_import::export::ExportArgs),
+    /// List the supported file types with their bucket, prefix, proto and tables
+    ListTypes,
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
@@ -24,8 +38,21 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    let db = huckli_db::Db::connect(&args.db)?;
+    if let Some(Command::ListTypes) = &args.command {
+        huckli_import::list_types();
+        return Ok(());
+    }
+
+    let db = huckli_db::Db::connect(args.db.as_deref().context("--db is required")?)?;
+
+    if let Some(Command::Export(export)) = &args.command {
+        return huckli_import::export::run(export, &db);
+    }
+
     let s3 = args.s3.connect().await;
 
-    huckli_import::run(args.file_type, &db, &s3, &args.selection).await
+    match args.file_type {
+        Some(file_type) => huckli_import::run(file_type, &db, &s3, &args.selection).await,
+        None => huckli_import::raw::run(&args.raw, &db, &s3, &args.selection).await,
+    }
 }
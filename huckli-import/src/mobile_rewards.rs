@@ -1,26 +1,38 @@
 use chrono::{DateTime, Utc};
-use helium_proto::{RewardManifest, services::poc_mobile};
+use helium_proto::{
+    RewardManifest,
+    services::poc_mobile::{self, MobileRewardShare},
+};
 use huckli_import_derive::Import;
 use uuid::Uuid;
 
-use crate::{PublicKeyBinary, determine_timestamp};
+use crate::{PublicKeyBinary, determine_timestamp, non_empty};
 
 mod radio_reward;
 
 #[allow(clippy::large_enum_variant)]
-#[derive(Debug)]
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = MobileRewardShare,
+    bucket = "helium-mainnet-mobile-verified",
+    prefix = "mobile_network_reward_shares_v1",
+    name = "mobile-rewards",
+))]
 pub enum MobileReward {
     Gateway(GatewayReward),
     Subscriber(SubscriberReward),
     ServiceProvider(ServiceProviderReward),
     Unallocated(UnallocatedReward),
     Promotion(PromotionReward),
-    Radio(radio_reward::Rewards),
-    Deprecated,
+    Radio(radio_reward::RadioReward),
+    RadioV1(radio_reward::RadioRewardV1),
+    Unknown,
 }
 
-impl From<poc_mobile::MobileRewardShare> for MobileReward {
-    fn from(value: poc_mobile::MobileRewardShare) -> Self {
+impl TryFrom<MobileRewardShare> for MobileReward {
+    type Error = anyhow::Error;
+
+    fn try_from(value: MobileRewardShare) -> anyhow::Result<Self> {
         let start = determine_timestamp(value.start_period);
         let end = determine_timestamp(value.end_period);
 
@@ -43,197 +55,130 @@ impl From<poc_mobile::MobileRewardShare> for MobileReward {
             Some(poc_mobile::mobile_reward_share::Reward::RadioRewardV2(r)) => {
                 r.to_mobile_reward(start, end)
             }
-            _ => MobileReward::Deprecated,
-        }
-    }
-}
-
-impl crate::DbTable for MobileReward {
-    fn create_table(db: &huckli_db::Db) -> anyhow::Result<()> {
-        GatewayReward::create_table(db)?;
-        SubscriberReward::create_table(db)?;
-        ServiceProviderReward::create_table(db)?;
-        UnallocatedReward::create_table(db)?;
-        PromotionReward::create_table(db)?;
-
-        radio_reward::Rewards::create_tables(db)?;
-
-        Ok(())
-    }
-
-    fn save(db: &huckli_db::Db, data: Vec<Self>) -> anyhow::Result<()> {
-        let mut gateway_rewards = Vec::new();
-        let mut subscriber_rewards = Vec::new();
-        let mut provider_rewards = Vec::new();
-        let mut unallocated_rewards = Vec::new();
-        let mut promotions = Vec::new();
-        let mut radios = Vec::new();
-
-        for mobile_reward in data {
-            match mobile_reward {
-                MobileReward::Gateway(gateway) => {
-                    gateway_rewards.push(gateway);
-                }
-                MobileReward::Subscriber(subscriber) => {
-                    subscriber_rewards.push(subscriber);
-                }
-                MobileReward::ServiceProvider(sp) => {
-                    provider_rewards.push(sp);
-                }
-                MobileReward::Unallocated(u) => {
-                    unallocated_rewards.push(u);
-                }
-                MobileReward::Promotion(p) => {
-                    promotions.push(p);
-                }
-                MobileReward::Radio(r) => {
-                    radios.push(r);
-                }
-                _ => (),
+            Some(poc_mobile::mobile_reward_share::Reward::RadioReward(r)) => {
+                r.to_mobile_reward(start, end)
             }
+            _ => Ok(MobileReward::Unknown),
         }
-
-        GatewayReward::save(db, gateway_rewards)?;
-        SubscriberReward::save(db, subscriber_rewards)?;
-        ServiceProviderReward::save(db, provider_rewards)?;
-        UnallocatedReward::save(db, unallocated_rewards)?;
-        PromotionReward::save(db, promotions)?;
-
-        radio_reward::Rewards::save(db, radios)?;
-
-        Ok(())
-    }
-}
-
-impl MobileReward {
-    pub async fn get_and_persist(
-        db: &huckli_db::Db,
-        s3: &huckli_s3::S3,
-        selection: &crate::FileSelectionArgs,
-    ) -> anyhow::Result<()> {
-        crate::get_and_persist::<poc_mobile::MobileRewardShare, MobileReward>(
-            db,
-            s3,
-            "helium-mainnet-mobile-verified",
-            "mobile_network_reward_shares_v1",
-            selection,
-        )
-        .await
     }
 }
 
 trait ToMobileReward {
-    fn to_mobile_reward(self, start: DateTime<Utc>, end: DateTime<Utc>) -> MobileReward;
+    fn to_mobile_reward(
+        self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> anyhow::Result<MobileReward>;
 }
 
 #[derive(Debug, Import)]
 #[import(table_name = "mobile_promotion_rewards")]
 pub struct PromotionReward {
-    #[import(sql = "timestamptz")]
     start_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_period: DateTime<Utc>,
     entity: String,
-    #[import(sql = "uint64")]
     service_provider_amount: u64,
-    #[import(sql = "uint64")]
     matched_amount: u64,
 }
 
 impl ToMobileReward for poc_mobile::PromotionReward {
-    fn to_mobile_reward(self, start: DateTime<Utc>, end: DateTime<Utc>) -> MobileReward {
-        MobileReward::Promotion(PromotionReward {
+    fn to_mobile_reward(
+        self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> anyhow::Result<MobileReward> {
+        Ok(MobileReward::Promotion(PromotionReward {
             start_period: start,
             end_period: end,
             entity: self.entity,
             service_provider_amount: self.service_provider_amount,
             matched_amount: self.matched_amount,
-        })
+        }))
     }
 }
 
 #[derive(Debug, Import)]
 #[import(table_name = "mobile_unallocated_rewards")]
 pub struct UnallocatedReward {
-    #[import(sql = "timestamptz")]
     start_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_period: DateTime<Utc>,
     reward_type: String,
-    #[import(sql = "uint64")]
     amount: u64,
 }
 
 impl ToMobileReward for poc_mobile::UnallocatedReward {
-    fn to_mobile_reward(self, start: DateTime<Utc>, end: DateTime<Utc>) -> MobileReward {
-        MobileReward::Unallocated(UnallocatedReward {
+    fn to_mobile_reward(
+        self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> anyhow::Result<MobileReward> {
+        Ok(MobileReward::Unallocated(UnallocatedReward {
             start_period: start,
             end_period: end,
             reward_type: self.reward_type().as_str_name().to_string(),
             amount: self.amount,
-        })
+        }))
     }
 }
 
 #[derive(Debug, Import)]
 #[import(table_name = "mobile_service_provider_rewards")]
 pub struct ServiceProviderReward {
-    #[import(sql = "timestamptz")]
     start_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_period: DateTime<Utc>,
     service_provider: String,
-    #[import(sql = "uint64")]
     amount: u64,
-    rewardable_entity_key: String,
+    rewardable_entity_key: Option<String>,
 }
 
 impl ToMobileReward for poc_mobile::ServiceProviderReward {
-    fn to_mobile_reward(self, start: DateTime<Utc>, end: DateTime<Utc>) -> MobileReward {
-        MobileReward::ServiceProvider(ServiceProviderReward {
+    fn to_mobile_reward(
+        self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> anyhow::Result<MobileReward> {
+        Ok(MobileReward::ServiceProvider(ServiceProviderReward {
             start_period: start,
             end_period: end,
             service_provider: self.service_provider_id().as_str_name().to_string(),
             amount: self.amount,
-            rewardable_entity_key: self.rewardable_entity_key,
-        })
+            rewardable_entity_key: non_empty(&self.rewardable_entity_key),
+        }))
     }
 }
 
 #[derive(Debug, Import)]
 #[import(table_name = "mobile_subscriber_rewards")]
 pub struct SubscriberReward {
-    #[import(sql = "timestamptz")]
     start_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_period: DateTime<Utc>,
     subscriber_id: String,
-    #[import(sql = "uint64")]
     discovery_location_amount: u64,
-    #[import(sql = "uint64")]
     verification_mapping_amount: u64,
-    reward_override_entity_key: String,
+    reward_override_entity_key: Option<String>,
 }
 
 impl ToMobileReward for poc_mobile::SubscriberReward {
-    fn to_mobile_reward(self, start: DateTime<Utc>, end: DateTime<Utc>) -> MobileReward {
-        MobileReward::Subscriber(SubscriberReward {
+    fn to_mobile_reward(
+        self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> anyhow::Result<MobileReward> {
+        Ok(MobileReward::Subscriber(SubscriberReward {
             start_period: start,
             end_period: end,
-            subscriber_id: Uuid::from_slice(&self.subscriber_id).unwrap().to_string(),
+            subscriber_id: Uuid::from_slice(&self.subscriber_id)?.to_string(),
             discovery_location_amount: self.discovery_location_amount,
             verification_mapping_amount: self.verification_mapping_amount,
-            reward_override_entity_key: self.reward_override_entity_key,
-        })
+            reward_override_entity_key: non_empty(&self.reward_override_entity_key),
+        }))
     }
 }
 
 #[derive(Debug, Import)]
 #[import(table_name = "mobile_gateway_rewards")]
 pub struct GatewayReward {
-    #[import(sql = "timestamptz")]
     start_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_period: DateTime<Utc>,
     hotspot_key: String,
     #[import(sql = "bigint")]
@@ -245,15 +190,19 @@ pub struct GatewayReward {
 }
 
 impl ToMobileReward for poc_mobile::GatewayReward {
-    fn to_mobile_reward(self, start: DateTime<Utc>, end: DateTime<Utc>) -> MobileReward {
-        MobileReward::Gateway(GatewayReward {
+    fn to_mobile_reward(
+        self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> anyhow::Result<MobileReward> {
+        Ok(MobileReward::Gateway(GatewayReward {
             start_period: start,
             end_period: end,
             hotspot_key: PublicKeyBinary::from(self.hotspot_key).to_string(),
             dc_transfer_reward: self.dc_transfer_reward,
             rewardable_bytes: self.rewardable_bytes,
             price: self.price,
-        })
+        }))
     }
 }
 
@@ -264,15 +213,10 @@ impl ToMobileReward for poc_mobile::GatewayReward {
     prefix = "network_reward_manifest_v1",
 ))]
 pub struct MobileRewardManifest {
-    #[import(sql = "timestamptz")]
     start_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_period: DateTime<Utc>,
-    #[import(sql = "uint64")]
     epoch: u64,
-    #[import(sql = "uint64")]
     price: u64,
-    #[import(sql = "json")]
     written_files: serde_json::Value,
 }
 
@@ -1,73 +1,21 @@
 use chrono::{DateTime, Utc};
 use helium_proto::services::poc_mobile;
 use huckli_import_derive::Import;
-use uuid::Uuid;
+use rust_decimal::Decimal;
 
-use crate::{DbTable, PublicKeyBinary, determine_timestamp, from_proto_decimal};
-
-#[derive(Debug)]
-pub struct Rewards {
-    radio: RadioReward,
-    trust_scores: Vec<LocationTrustScore>,
-    speedtests: Vec<Speedtest>,
-    covered_hexes: Vec<CoveredHex>,
-}
+use crate::{
+    PublicKeyBinary, determine_optional_timestamp, determine_timestamp, from_proto_decimal,
+    non_empty, optional_uuid,
+};
 
 impl super::ToMobileReward for poc_mobile::RadioRewardV2 {
-    fn to_mobile_reward(self, start: DateTime<Utc>, end: DateTime<Utc>) -> super::MobileReward {
-        let radio = RadioReward::from((start, end, &self));
-        let id = radio.id.clone();
-
-        super::MobileReward::Radio(Rewards {
-            radio,
-            trust_scores: self
-                .location_trust_scores
-                .iter()
-                .map(|lts| LocationTrustScore::from((id.clone(), lts)))
-                .collect(),
-            speedtests: self
-                .speedtests
-                .iter()
-                .map(|st| Speedtest::from((id.clone(), st)))
-                .collect(),
-            covered_hexes: self
-                .covered_hexes
-                .iter()
-                .map(|ch| CoveredHex::from((id.clone(), ch)))
-                .collect(),
-        })
-    }
-}
-
-impl Rewards {
-    pub fn create_tables(db: &huckli_db::Db) -> anyhow::Result<()> {
-        RadioReward::create_table(db)?;
-        LocationTrustScore::create_table(db)?;
-        Speedtest::create_table(db)?;
-        CoveredHex::create_table(db)?;
-
-        Ok(())
-    }
-
-    pub fn save(db: &huckli_db::Db, rewards: Vec<Rewards>) -> anyhow::Result<()> {
-        let mut radios = Vec::new();
-        let mut trust_scores = Vec::new();
-        let mut speedtests = Vec::new();
-        let mut hexes = Vec::new();
-
-        for mut r in rewards {
-            radios.push(r.radio);
-            trust_scores.append(&mut r.trust_scores);
-            speedtests.append(&mut r.speedtests);
-            hexes.append(&mut r.covered_hexes);
-        }
-
-        RadioReward::save(db, radios)?;
-        LocationTrustScore::save(db, trust_scores)?;
-        Speedtest::save(db, speedtests)?;
-        CoveredHex::save(db, hexes)?;
-
-        Ok(())
+    fn to_mobile_reward(
+        self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> anyhow::Result<super::MobileReward> {
+        let reward = RadioReward::try_from((start, end, &self))?;
+        Ok(super::MobileReward::Radio(reward))
     }
 }
 
@@ -75,48 +23,44 @@ impl Rewards {
 #[import(table_name = "mobile_radio_rewards")]
 pub struct RadioReward {
     id: String,
-    #[import(sql = "timestamptz")]
     start_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_period: DateTime<Utc>,
     hotspot_key: String,
-    #[import(sql = "double")]
-    base_coverage_points_sum: f64,
-    #[import(sql = "double")]
-    boosted_coverage_points_sum: f64,
-    #[import(sql = "double")]
-    base_reward_shares: f64,
-    #[import(sql = "double")]
-    boosted_reward_shares: f64,
-    #[import(sql = "uint64")]
+    base_coverage_points_sum: Option<Decimal>,
+    boosted_coverage_points_sum: Option<Decimal>,
+    base_reward_shares: Option<Decimal>,
+    boosted_reward_shares: Option<Decimal>,
     base_poc_reward: u64,
-    #[import(sql = "uint64")]
     boosted_poc_reward: u64,
-    #[import(sql = "timestamptz")]
     seniority_timestamp: DateTime<Utc>,
-    coverage_object: String,
-    #[import(sql = "double")]
-    location_trust_score_multiplier: f64,
-    #[import(sql = "double")]
-    speedtest_multiplier: f64,
+    coverage_object: Option<String>,
+    location_trust_score_multiplier: Option<Decimal>,
+    speedtest_multiplier: Option<Decimal>,
     sp_boosted_hex_status: String,
     oracle_boosted_hex_status: String,
-    #[import(sql = "uint64")]
-    speedtest_avg_upload: u64,
-    #[import(sql = "uint64")]
-    speedtest_avg_download: u64,
-    #[import(sql = "uint32")]
-    speedtest_avg_latency_ms: u32,
-    #[import(sql = "timestamptz")]
-    speedtest_avg_timestamp: DateTime<Utc>,
+    speedtest_avg_upload: Option<u64>,
+    speedtest_avg_download: Option<u64>,
+    speedtest_avg_latency_ms: Option<u32>,
+    speedtest_avg_timestamp: Option<DateTime<Utc>>,
+    #[import(children)]
+    trust_scores: Vec<LocationTrustScore>,
+    #[import(children)]
+    speedtests: Vec<Speedtest>,
+    #[import(children)]
+    covered_hexes: Vec<CoveredHex>,
 }
 
-impl From<(DateTime<Utc>, DateTime<Utc>, &poc_mobile::RadioRewardV2)> for RadioReward {
-    fn from(value: (DateTime<Utc>, DateTime<Utc>, &poc_mobile::RadioRewardV2)) -> Self {
+impl TryFrom<(DateTime<Utc>, DateTime<Utc>, &poc_mobile::RadioRewardV2)> for RadioReward {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        value: (DateTime<Utc>, DateTime<Utc>, &poc_mobile::RadioRewardV2),
+    ) -> anyhow::Result<Self> {
         let (start, end, reward) = value;
         let id = uuid::Uuid::new_v4().to_string();
+        let speedtest_average = reward.speedtest_average.as_ref();
 
-        Self {
+        Ok(Self {
             id,
             start_period: start,
             end_period: end,
@@ -130,59 +74,108 @@ impl From<(DateTime<Utc>, DateTime<Utc>, &poc_mobile::RadioRewardV2)> for RadioR
             base_poc_reward: reward.base_poc_reward,
             boosted_poc_reward: reward.boosted_poc_reward,
             seniority_timestamp: determine_timestamp(reward.seniority_timestamp),
-            coverage_object: Uuid::from_slice(&reward.coverage_object)
-                .unwrap()
-                .to_string(),
+            coverage_object: optional_uuid(&reward.coverage_object)?,
             location_trust_score_multiplier: from_proto_decimal(
                 reward.location_trust_score_multiplier.as_ref(),
             ),
             speedtest_multiplier: from_proto_decimal(reward.speedtest_multiplier.as_ref()),
             sp_boosted_hex_status: reward.sp_boosted_hex_status().as_str_name().to_string(),
             oracle_boosted_hex_status: reward.oracle_boosted_hex_status().as_str_name().to_string(),
-            speedtest_avg_upload: reward.speedtest_average.as_ref().unwrap().upload_speed_bps,
-            speedtest_avg_download: reward
-                .speedtest_average
-                .as_ref()
-                .unwrap()
-                .download_speed_bps,
-            speedtest_avg_latency_ms: reward.speedtest_average.as_ref().unwrap().latency_ms,
-            speedtest_avg_timestamp: determine_timestamp(
-                reward.speedtest_average.as_ref().unwrap().timestamp,
+            speedtest_avg_upload: speedtest_average.map(|s| s.upload_speed_bps),
+            speedtest_avg_download: speedtest_average.map(|s| s.download_speed_bps),
+            speedtest_avg_latency_ms: speedtest_average.map(|s| s.latency_ms),
+            speedtest_avg_timestamp: speedtest_average
+                .and_then(|s| determine_optional_timestamp(s.timestamp)),
+            trust_scores: reward
+                .location_trust_scores
+                .iter()
+                .map(LocationTrustScore::from)
+                .collect(),
+            speedtests: reward.speedtests.iter().map(Speedtest::from).collect(),
+            covered_hexes: reward.covered_hexes.iter().map(CoveredHex::from).collect(),
+        })
+    }
+}
+
+#[derive(Debug, Import)]
+#[import(table_name = "mobile_radio_rewards_v1")]
+pub struct RadioRewardV1 {
+    start_period: DateTime<Utc>,
+    end_period: DateTime<Utc>,
+    hotspot_key: String,
+    cbsd_id: Option<String>,
+    poc_reward: u64,
+    coverage_points: u64,
+    seniority_timestamp: DateTime<Utc>,
+    coverage_object: Option<String>,
+    location_trust_score_multiplier: Decimal,
+    speedtest_multiplier: Decimal,
+    boosted_hexes: serde_json::Value,
+}
+
+impl super::ToMobileReward for poc_mobile::RadioReward {
+    fn to_mobile_reward(
+        self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> anyhow::Result<super::MobileReward> {
+        let boosted_hexes = self
+            .boosted_hexes
+            .iter()
+            .map(|h| {
+                let mut m = serde_json::Map::new();
+                m.insert(
+                    "location".to_string(),
+                    serde_json::Value::Number(h.location.into()),
+                );
+                m.insert(
+                    "multiplier".to_string(),
+                    serde_json::Value::Number(h.multiplier.into()),
+                );
+
+                serde_json::Value::Object(m)
+            })
+            .collect::<Vec<_>>();
+
+        Ok(super::MobileReward::RadioV1(RadioRewardV1 {
+            start_period: start,
+            end_period: end,
+            hotspot_key: PublicKeyBinary::from(self.hotspot_key).to_string(),
+            cbsd_id: non_empty(&self.cbsd_id),
+            poc_reward: self.poc_reward,
+            coverage_points: self.coverage_points,
+            seniority_timestamp: determine_timestamp(self.seniority_timestamp),
+            coverage_object: optional_uuid(&self.coverage_object)?,
+            // v1 rewards carry multipliers scaled by 1000
+            location_trust_score_multiplier: Decimal::new(
+                self.location_trust_score_multiplier.into(),
+                3,
             ),
-        }
+            speedtest_multiplier: Decimal::new(self.speedtest_multiplier.into(), 3),
+            boosted_hexes: serde_json::Value::Array(boosted_hexes),
+        }))
     }
 }
 
 #[derive(Debug, Import)]
-#[import(table_name = "mobile_reward_covered_hexes")]
+#[import(child, table_name = "mobile_reward_covered_hexes")]
 pub struct CoveredHex {
-    id: String,
-    #[import(sql = "uint64")]
     location: u64,
-    #[import(sql = "double")]
-    base_coverage_points: f64,
-    #[import(sql = "double")]
-    boosted_coverage_points: f64,
+    base_coverage_points: Option<Decimal>,
+    boosted_coverage_points: Option<Decimal>,
     urbanized: String,
     footfall: String,
     landtype: String,
-    #[import(sql = "double")]
-    assignment_multiplier: f64,
-    #[import(sql = "uint32")]
+    assignment_multiplier: Option<Decimal>,
     rank: u32,
-    #[import(sql = "double")]
-    rank_multiplier: f64,
-    #[import(sql = "uint32")]
+    rank_multiplier: Option<Decimal>,
     boosted_multiplier: u32,
-    #[import(sql = "bool")]
     service_provider_override: bool,
 }
 
-impl From<(String, &poc_mobile::radio_reward_v2::CoveredHex)> for CoveredHex {
-    fn from(value: (String, &poc_mobile::radio_reward_v2::CoveredHex)) -> Self {
-        let (id, hex) = value;
+impl From<&poc_mobile::radio_reward_v2::CoveredHex> for CoveredHex {
+    fn from(hex: &poc_mobile::radio_reward_v2::CoveredHex) -> Self {
         Self {
-            id,
             location: hex.location,
             base_coverage_points: from_proto_decimal(hex.base_coverage_points.as_ref()),
             boosted_coverage_points: from_proto_decimal(hex.boosted_coverage_points.as_ref()),
@@ -199,24 +192,17 @@ impl From<(String, &poc_mobile::radio_reward_v2::CoveredHex)> for CoveredHex {
 }
 
 #[derive(Debug, Import)]
-#[import(table_name = "mobile_reward_speedtests")]
+#[import(child, table_name = "mobile_reward_speedtests")]
 pub struct Speedtest {
-    id: String,
-    #[import(sql = "uint64")]
     upload: u64,
-    #[import(sql = "uint64")]
     download: u64,
-    #[import(sql = "uint32")]
     latency_ms: u32,
-    #[import(sql = "timestamptz")]
     timestamp: DateTime<Utc>,
 }
 
-impl From<(String, &poc_mobile::Speedtest)> for Speedtest {
-    fn from(value: (String, &poc_mobile::Speedtest)) -> Self {
-        let (id, st) = value;
+impl From<&poc_mobile::Speedtest> for Speedtest {
+    fn from(st: &poc_mobile::Speedtest) -> Self {
         Self {
-            id,
             upload: st.upload_speed_bps,
             download: st.download_speed_bps,
             latency_ms: st.latency_ms,
@@ -226,20 +212,15 @@ impl From<(String, &poc_mobile::Speedtest)> for Speedtest {
 }
 
 #[derive(Debug, Import)]
-#[import(table_name = "mobile_reward_trust_scores")]
+#[import(child, table_name = "mobile_reward_trust_scores")]
 pub struct LocationTrustScore {
-    id: String,
-    #[import(sql = "uint64")]
     meters_to_asserted: u64,
-    #[import(sql = "double")]
-    trust_score: f64,
+    trust_score: Option<Decimal>,
 }
 
-impl From<(String, &poc_mobile::radio_reward_v2::LocationTrustScore)> for LocationTrustScore {
-    fn from(value: (String, &poc_mobile::radio_reward_v2::LocationTrustScore)) -> Self {
-        let (id, lts) = value;
+impl From<&poc_mobile::radio_reward_v2::LocationTrustScore> for LocationTrustScore {
+    fn from(lts: &poc_mobile::radio_reward_v2::LocationTrustScore) -> Self {
         Self {
-            id,
             meters_to_asserted: lts.meters_to_asserted,
             trust_score: from_proto_decimal(lts.trust_score.as_ref()),
         }
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use helium_proto::services::packet_router::PacketRouterPacketReportV1;
+use huckli_import_derive::Import;
+
+use crate::{PublicKeyBinary, determine_timestamp};
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = PacketRouterPacketReportV1,
+    bucket = "helium-mainnet-iot-ingest",
+    prefix = "packetreport",
+    name = "packet-router-packet-report",
+))]
+pub struct PacketReport {
+    gateway: String,
+    gateway_tmst: u64,
+    oui: u64,
+    net_id: u32,
+    rssi: i32,
+    snr: f64,
+    frequency: u32,
+    datarate: String,
+    region: String,
+    payload_hash: Vec<u8>,
+    payload_size: u32,
+    free: bool,
+    packet_type: String,
+    received_timestamp: DateTime<Utc>,
+}
+
+impl From<PacketRouterPacketReportV1> for PacketReport {
+    fn from(value: PacketRouterPacketReportV1) -> Self {
+        Self {
+            gateway: PublicKeyBinary::from(value.gateway.clone()).to_string(),
+            gateway_tmst: value.gateway_tmst,
+            oui: value.oui,
+            net_id: value.net_id,
+            rssi: value.rssi,
+            snr: value.snr as f64,
+            frequency: value.frequency,
+            datarate: value.datarate().as_str_name().to_string(),
+            region: value.region().as_str_name().to_string(),
+            payload_hash: value.payload_hash.clone(),
+            payload_size: value.payload_size,
+            free: value.free,
+            packet_type: value.r#type().as_str_name().to_string(),
+            received_timestamp: determine_timestamp(value.received_timestamp),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use helium_proto::PriceReportV1;
+use huckli_import_derive::Import;
+
+use crate::determine_timestamp;
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = PriceReportV1,
+    bucket = "helium-mainnet-price",
+    prefix = "price_report",
+))]
+pub struct PriceReport {
+    token_type: String,
+    price: u64,
+    timestamp: DateTime<Utc>,
+}
+
+impl From<PriceReportV1> for PriceReport {
+    fn from(value: PriceReportV1) -> Self {
+        Self {
+            token_type: value.token_type().as_str_name().to_string(),
+            price: value.price,
+            timestamp: determine_timestamp(value.timestamp),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use helium_proto::services::poc_mobile::PromotionRewardIngestReportV1;
+use huckli_import_derive::Import;
+
+use crate::{PublicKeyBinary, determine_timestamp};
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = PromotionRewardIngestReportV1,
+    bucket = "helium-mainnet-mobile-ingest",
+    prefix = "promotion_reward_ingest_report",
+))]
+pub struct PromotionRewardIngest {
+    entity: String,
+    shares: u64,
+    timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    carrier_pub_key: String,
+}
+
+impl From<PromotionRewardIngestReportV1> for PromotionRewardIngest {
+    fn from(value: PromotionRewardIngestReportV1) -> Self {
+        let req = value.report.as_ref().unwrap();
+
+        Self {
+            entity: req.entity.clone(),
+            shares: req.shares,
+            timestamp: determine_timestamp(req.timestamp),
+            received_timestamp: determine_timestamp(value.received_timestamp),
+            carrier_pub_key: PublicKeyBinary::from(req.carrier_pub_key.clone()).to_string(),
+        }
+    }
+}
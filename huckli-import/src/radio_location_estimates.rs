@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use helium_proto::services::poc_mobile::{
+    RadioLocationEstimateV1, RadioLocationEstimatesIngestReportV1, RadioLocationEstimatesReqV1,
+    VerifiedRadioLocationEstimatesReportV1,
+};
+use huckli_import_derive::Import;
+use rust_decimal::Decimal;
+
+use crate::{PublicKeyBinary, determine_timestamp, from_proto_decimal};
+
+#[derive(Debug, Import)]
+#[import(table_name = "radio_location_estimates_ingest")]
+#[import(s3decode(
+    proto = RadioLocationEstimatesIngestReportV1,
+    bucket = "helium-mainnet-mobile-ingest",
+    prefix = "radio_location_estimates_ingest_report",
+))]
+pub struct RadioLocationEstimatesIngest {
+    id: String,
+    hotspot_key: String,
+    carrier_key: String,
+    timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    #[import(children, table_name = "radio_location_estimate_ingest")]
+    estimates: Vec<RadioLocationEstimate>,
+}
+
+impl From<RadioLocationEstimatesIngestReportV1> for RadioLocationEstimatesIngest {
+    fn from(value: RadioLocationEstimatesIngestReportV1) -> Self {
+        let req = value.report.as_ref().unwrap();
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            hotspot_key: PublicKeyBinary::from(req.hotspot_key.clone()).to_string(),
+            carrier_key: PublicKeyBinary::from(req.carrier_key.clone()).to_string(),
+            timestamp: determine_timestamp(req.timestamp),
+            received_timestamp: determine_timestamp(value.received_timestamp),
+            estimates: estimates(req),
+        }
+    }
+}
+
+#[derive(Debug, Import)]
+#[import(table_name = "verified_radio_location_estimates")]
+#[import(s3decode(
+    proto = VerifiedRadioLocationEstimatesReportV1,
+    bucket = "helium-mainnet-mobile-verified",
+    prefix = "verified_radio_location_estimates_report",
+))]
+pub struct VerifiedRadioLocationEstimates {
+    id: String,
+    hotspot_key: String,
+    carrier_key: String,
+    timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    verified_timestamp: DateTime<Utc>,
+    status: String,
+    #[import(children, table_name = "verified_radio_location_estimate")]
+    estimates: Vec<RadioLocationEstimate>,
+}
+
+impl From<VerifiedRadioLocationEstimatesReportV1> for VerifiedRadioLocationEstimates {
+    fn from(value: VerifiedRadioLocationEstimatesReportV1) -> Self {
+        let ingest = value.report.as_ref().unwrap();
+        let req = ingest.report.as_ref().unwrap();
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            hotspot_key: PublicKeyBinary::from(req.hotspot_key.clone()).to_string(),
+            carrier_key: PublicKeyBinary::from(req.carrier_key.clone()).to_string(),
+            timestamp: determine_timestamp(req.timestamp),
+            received_timestamp: determine_timestamp(ingest.received_timestamp),
+            verified_timestamp: determine_timestamp(value.timestamp),
+            status: value.status().as_str_name().to_string(),
+            estimates: estimates(req),
+        }
+    }
+}
+
+fn estimates(req: &RadioLocationEstimatesReqV1) -> Vec<RadioLocationEstimate> {
+    req.estimates
+        .iter()
+        .map(RadioLocationEstimate::from)
+        .collect()
+}
+
+fn events(estimate: &RadioLocationEstimateV1) -> serde_json::Value {
+    let events = estimate
+        .events
+        .iter()
+        .map(|ev| {
+            let mut m = serde_json::Map::new();
+            m.insert("id".to_string(), serde_json::Value::String(ev.id.clone()));
+            m.insert(
+                "timestamp".to_string(),
+                serde_json::Value::String(determine_timestamp(ev.timestamp).to_rfc3339()),
+            );
+
+            serde_json::Value::Object(m)
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::Value::Array(events)
+}
+
+// Stored in a separate table under each parent, which names it.
+#[derive(Debug, Import)]
+#[import(child)]
+pub struct RadioLocationEstimate {
+    lat: Option<Decimal>,
+    lon: Option<Decimal>,
+    radius: Option<Decimal>,
+    confidence: Option<Decimal>,
+    events: serde_json::Value,
+}
+
+impl From<&RadioLocationEstimateV1> for RadioLocationEstimate {
+    fn from(value: &RadioLocationEstimateV1) -> Self {
+        Self {
+            lat: from_proto_decimal(value.lat.as_ref()),
+            lon: from_proto_decimal(value.lon.as_ref()),
+            radius: from_proto_decimal(value.radius.as_ref()),
+            confidence: from_proto_decimal(value.confidence.as_ref()),
+            events: events(value),
+        }
+    }
+}
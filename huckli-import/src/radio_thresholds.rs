@@ -1,11 +1,78 @@
 use chrono::{DateTime, Utc};
 use helium_proto::services::poc_mobile::{
+    InvalidatedRadioThresholdIngestReportV1, RadioThresholdIngestReportV1,
     VerifiedInvalidatedRadioThresholdIngestReportV1, VerifiedRadioThresholdIngestReportV1,
 };
 use huckli_import_derive::Import;
 
 use crate::{PublicKeyBinary, determine_timestamp};
 
+fn radio_key(hotspot_pubkey: &[u8], cbsd_id: &str) -> String {
+    if !hotspot_pubkey.is_empty() {
+        PublicKeyBinary::from(hotspot_pubkey.to_vec()).to_string()
+    } else {
+        cbsd_id.to_string()
+    }
+}
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = RadioThresholdIngestReportV1,
+    bucket = "helium-mainnet-mobile-ingest",
+    prefix = "radio_threshold_ingest_report",
+))]
+pub struct RadioThresholdIngest {
+    radio_key: String,
+    bytes_threshold: u64,
+    subscriber_threshold: u32,
+    threshold_timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    carrier_pub_key: String,
+}
+
+impl From<RadioThresholdIngestReportV1> for RadioThresholdIngest {
+    fn from(value: RadioThresholdIngestReportV1) -> Self {
+        let req = value.report.as_ref().unwrap();
+
+        Self {
+            radio_key: radio_key(&req.hotspot_pubkey, &req.cbsd_id),
+            bytes_threshold: req.bytes_threshold,
+            subscriber_threshold: req.subscriber_threshold,
+            threshold_timestamp: determine_timestamp(req.threshold_timestamp),
+            received_timestamp: determine_timestamp(value.received_timestamp),
+            carrier_pub_key: PublicKeyBinary::from(req.carrier_pub_key.clone()).to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = InvalidatedRadioThresholdIngestReportV1,
+    bucket = "helium-mainnet-mobile-ingest",
+    prefix = "invalidated_radio_threshold_ingest_report",
+))]
+pub struct InvalidatedRadioThresholdIngest {
+    radio_key: String,
+    reason: String,
+    threshold_timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    carrier_pub_key: String,
+}
+
+impl From<InvalidatedRadioThresholdIngestReportV1> for InvalidatedRadioThresholdIngest {
+    fn from(value: InvalidatedRadioThresholdIngestReportV1) -> Self {
+        let req = value.report.as_ref().unwrap();
+
+        Self {
+            radio_key: radio_key(&req.hotspot_pubkey, &req.cbsd_id),
+            reason: req.reason().as_str_name().to_string(),
+            threshold_timestamp: determine_timestamp(req.timestamp),
+            received_timestamp: determine_timestamp(value.received_timestamp),
+            carrier_pub_key: PublicKeyBinary::from(req.carrier_pub_key.clone()).to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Import)]
 #[import(s3decode(
     proto = VerifiedRadioThresholdIngestReportV1,
@@ -14,15 +81,10 @@ use crate::{PublicKeyBinary, determine_timestamp};
 ))]
 pub struct VerifiedRadioThreshold {
     radio_key: String,
-    #[import(sql = "uint64")]
     bytes_threshold: u64,
-    #[import(sql = "uint32")]
     subscriber_threshold: u32,
-    #[import(sql = "timestamptz")]
     threshold_timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     received_timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     verified_timestamp: DateTime<Utc>,
     status: String,
 }
@@ -32,14 +94,8 @@ impl From<VerifiedRadioThresholdIngestReportV1> for VerifiedRadioThreshold {
         let ingest = value.report.as_ref().unwrap();
         let req = ingest.report.as_ref().unwrap();
 
-        let radio_key = if !req.hotspot_pubkey.is_empty() {
-            PublicKeyBinary::from(req.hotspot_pubkey.clone()).to_string()
-        } else {
-            req.cbsd_id.clone()
-        };
-
         Self {
-            radio_key,
+            radio_key: radio_key(&req.hotspot_pubkey, &req.cbsd_id),
             bytes_threshold: req.bytes_threshold,
             subscriber_threshold: req.subscriber_threshold,
             threshold_timestamp: determine_timestamp(req.threshold_timestamp),
@@ -59,11 +115,8 @@ impl From<VerifiedRadioThresholdIngestReportV1> for VerifiedRadioThreshold {
 pub struct VerifiedInvalidatedRadioThreshold {
     radio_key: String,
     reason: String,
-    #[import(sql = "timestamptz")]
     threshold_timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     received_timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     verified_timestamp: DateTime<Utc>,
     status: String,
 }
@@ -73,14 +126,8 @@ impl From<VerifiedInvalidatedRadioThresholdIngestReportV1> for VerifiedInvalidat
         let ingest = value.report.as_ref().unwrap();
         let req = ingest.report.as_ref().unwrap();
 
-        let radio_key = if !req.hotspot_pubkey.is_empty() {
-            PublicKeyBinary::from(req.hotspot_pubkey.clone()).to_string()
-        } else {
-            req.cbsd_id.clone()
-        };
-
         Self {
-            radio_key,
+            radio_key: radio_key(&req.hotspot_pubkey, &req.cbsd_id),
             reason: req.reason().as_str_name().to_string(),
             threshold_timestamp: determine_timestamp(req.timestamp),
             received_timestamp: determine_timestamp(ingest.received_timestamp),
@@ -0,0 +1,132 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use huckli_import_derive::Import;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, SerializeOptions};
+
+use crate::FileSelectionArgs;
+
+const SERIALIZE_OPTIONS: SerializeOptions = SerializeOptions::new()
+    .stringify_64_bit_integers(false)
+    .use_proto_field_name(true)
+    .skip_default_fields(false);
+
+// Every helium-proto message, compiled by build.rs.
+const HELIUM_PROTO_DESCRIPTOR_SET: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/helium_proto.bin"));
+
+#[derive(Debug, clap::Args)]
+pub struct RawArgs {
+    /// Fully qualified message name to decode, e.g. helium.poc_mobile.seniority_update
+    #[arg(long, conflicts_with = "file_type")]
+    message: Option<String>,
+    /// File descriptor set to decode with instead of the built-in helium-proto descriptors
+    /// (protoc --include_imports --descriptor_set_out)
+    #[arg(long, requires = "message")]
+    descriptor_set: Option<PathBuf>,
+}
+
+#[derive(Debug, Import)]
+#[import(table_name = "raw_records")]
+pub struct RawRecord {
+    message_type: String,
+    file_timestamp: DateTime<Utc>,
+    record: serde_json::Value,
+}
+
+impl RawRecord {
+    fn decode(
+        descriptor: &MessageDescriptor,
+        file_timestamp: DateTime<Utc>,
+        bytes: Bytes,
+    ) -> anyhow::Result<Self> {
+        let record = DynamicMessage::decode(descriptor.clone(), bytes)?
+            .serialize_with_options(serde_json::value::Serializer, &SERIALIZE_OPTIONS)?;
+
+        Ok(Self {
+            message_type: descriptor.full_name().to_string(),
+            file_timestamp,
+            record,
+        })
+    }
+}
+
+fn helium_proto_pool() -> anyhow::Result<DescriptorPool> {
+    Ok(DescriptorPool::decode(HELIUM_PROTO_DESCRIPTOR_SET)?)
+}
+
+pub async fn run(
+    args: &RawArgs,
+    db: &huckli_db::Db,
+    s3: &huckli_s3::S3,
+    selection: &FileSelectionArgs,
+) -> anyhow::Result<()> {
+    let message = args
+        .message
+        .as_deref()
+        .context("--message is required for raw imports")?;
+    let bucket = s3
+        .bucket()
+        .context("--bucket is required for raw imports")?;
+    let prefix = s3
+        .prefix()
+        .context("--prefix is required for raw imports")?;
+
+    let pool = match &args.descriptor_set {
+        Some(path) => DescriptorPool::decode(tokio::fs::read(path).await?.as_slice())?,
+        None => helium_proto_pool()?,
+    };
+    let descriptor = pool
+        .get_message_by_name(message)
+        .with_context(|| format!("message {message} not found in descriptor set"))?;
+
+    crate::get_and_persist(db, s3, bucket, prefix, selection, |file, bytes| {
+        RawRecord::decode(&descriptor, file.timestamp, bytes)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use helium_proto::{BlockchainTokenTypeV1, PriceReportV1};
+    use prost::Message;
+
+    use super::*;
+
+    #[test]
+    fn decodes_helium_proto_messages_to_json() {
+        let pool = helium_proto_pool().unwrap();
+        let descriptor = pool.get_message_by_name("helium.price_report_v1").unwrap();
+        let report = PriceReportV1 {
+            price: 123_456,
+            timestamp: 1_700_000_000,
+            token_type: BlockchainTokenTypeV1::Mobile.into(),
+        };
+        let file_timestamp = Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap();
+
+        let record =
+            RawRecord::decode(&descriptor, file_timestamp, report.encode_to_vec().into()).unwrap();
+
+        assert_eq!(record.message_type, "helium.price_report_v1");
+        assert_eq!(record.file_timestamp, file_timestamp);
+        assert_eq!(
+            record.record,
+            serde_json::json!({
+                "price": 123_456,
+                "timestamp": 1_700_000_000,
+                "token_type": BlockchainTokenTypeV1::Mobile.as_str_name(),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_bytes_that_do_not_match_the_message() {
+        let pool = helium_proto_pool().unwrap();
+        let descriptor = pool.get_message_by_name("helium.price_report_v1").unwrap();
+
+        assert!(RawRecord::decode(&descriptor, Utc::now(), Bytes::from_static(&[0xff])).is_err());
+    }
+}
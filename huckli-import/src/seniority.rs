@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use helium_proto::services::poc_mobile::{
+    SeniorityUpdate as SeniorityUpdateProto, seniority_update,
+};
+use huckli_import_derive::Import;
+
+use crate::{PublicKeyBinary, determine_timestamp};
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = SeniorityUpdateProto,
+    bucket = "helium-mainnet-mobile-verified",
+    prefix = "seniority_update",
+))]
+pub struct SeniorityUpdate {
+    radio_key: String,
+    new_seniority_timestamp: DateTime<Utc>,
+    reason: String,
+    timestamp: DateTime<Utc>,
+}
+
+impl TryFrom<SeniorityUpdateProto> for SeniorityUpdate {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SeniorityUpdateProto) -> anyhow::Result<Self> {
+        let radio_key = match value.key_type.as_ref() {
+            Some(seniority_update::KeyType::HotspotKey(key)) => {
+                PublicKeyBinary::from(key.clone()).to_string()
+            }
+            Some(seniority_update::KeyType::CbsdId(cbsd_id)) => cbsd_id.to_string(),
+            _ => anyhow::bail!("seniority update has no supported key type"),
+        };
+
+        Ok(Self {
+            radio_key,
+            new_seniority_timestamp: determine_timestamp(value.new_seniority_timestamp),
+            reason: value.reason().as_str_name().to_string(),
+            timestamp: determine_timestamp(value.timestamp),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_updates_without_a_key_type() {
+        assert!(SeniorityUpdate::try_from(SeniorityUpdateProto::default()).is_err());
+    }
+}
@@ -1,11 +1,56 @@
+use anyhow::Context;
 use chrono::{DateTime, Utc};
 use helium_proto::services::poc_mobile::{
+    ServiceProviderBoostedRewardsBannedRadioIngestReportV1,
+    ServiceProviderBoostedRewardsBannedRadioReqV1,
     VerifiedServiceProviderBoostedRewardsBannedRadioIngestReportV1,
     service_provider_boosted_rewards_banned_radio_req_v1::KeyType,
 };
 use huckli_import_derive::Import;
 
-use crate::{PublicKeyBinary, determine_timestamp};
+use crate::{PublicKeyBinary, determine_optional_timestamp, determine_timestamp};
+
+fn hotspot_key(req: &ServiceProviderBoostedRewardsBannedRadioReqV1) -> anyhow::Result<String> {
+    match req.key_type.as_ref() {
+        Some(KeyType::HotspotKey(key)) => Ok(PublicKeyBinary::from(key.clone()).to_string()),
+        Some(KeyType::CbsdId(cbsd_id)) => Ok(cbsd_id.to_string()),
+        _ => anyhow::bail!("banned radio report has no supported key type"),
+    }
+}
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = ServiceProviderBoostedRewardsBannedRadioIngestReportV1,
+    bucket = "helium-mainnet-mobile-ingest",
+    prefix = "service_provider_boosted_rewards_banned_radio",
+))]
+pub struct ServiceProviderBannedRadioIngest {
+    hotspot_key: String,
+    reason: String,
+    until: Option<DateTime<Utc>>,
+    ban_type: String,
+    received_timestamp: DateTime<Utc>,
+}
+
+impl TryFrom<ServiceProviderBoostedRewardsBannedRadioIngestReportV1>
+    for ServiceProviderBannedRadioIngest
+{
+    type Error = anyhow::Error;
+
+    fn try_from(
+        value: ServiceProviderBoostedRewardsBannedRadioIngestReportV1,
+    ) -> anyhow::Result<Self> {
+        let req = value.report.as_ref().context("missing report")?;
+
+        Ok(Self {
+            hotspot_key: hotspot_key(req)?,
+            reason: req.reason().as_str_name().to_string(),
+            until: determine_optional_timestamp(req.until),
+            ban_type: req.ban_type().as_str_name().to_string(),
+            received_timestamp: determine_timestamp(value.received_timestamp),
+        })
+    }
+}
 
 #[derive(Debug, Import)]
 #[import(s3decode(
@@ -16,37 +61,47 @@ use crate::{PublicKeyBinary, determine_timestamp};
 pub struct VerifiedCdrVerification {
     hotspot_key: String,
     reason: String,
-    #[import(sql = "timestamptz")]
-    until: DateTime<Utc>,
+    until: Option<DateTime<Utc>>,
     ban_type: String,
-    #[import(sql = "timestamptz")]
     received_timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     verified_timestamp: DateTime<Utc>,
     status: String,
 }
 
-impl From<VerifiedServiceProviderBoostedRewardsBannedRadioIngestReportV1>
+impl TryFrom<VerifiedServiceProviderBoostedRewardsBannedRadioIngestReportV1>
     for VerifiedCdrVerification
 {
-    fn from(value: VerifiedServiceProviderBoostedRewardsBannedRadioIngestReportV1) -> Self {
-        let ingest = value.report.as_ref().unwrap();
-        let req = ingest.report.as_ref().unwrap();
-
-        let hotspot_key = match req.key_type.as_ref() {
-            Some(KeyType::HotspotKey(key)) => PublicKeyBinary::from(key.clone()).to_string(),
-            Some(KeyType::CbsdId(cbsd_id)) => cbsd_id.to_string(),
-            _ => panic!("unknown key type"),
-        };
+    type Error = anyhow::Error;
 
-        Self {
-            hotspot_key,
+    fn try_from(
+        value: VerifiedServiceProviderBoostedRewardsBannedRadioIngestReportV1,
+    ) -> anyhow::Result<Self> {
+        let ingest = value.report.as_ref().context("missing ingest report")?;
+        let req = ingest.report.as_ref().context("missing report")?;
+
+        Ok(Self {
+            hotspot_key: hotspot_key(req)?,
             reason: req.reason().as_str_name().to_string(),
-            until: determine_timestamp(req.until),
+            until: determine_optional_timestamp(req.until),
             ban_type: req.ban_type().as_str_name().to_string(),
             received_timestamp: determine_timestamp(ingest.received_timestamp),
             verified_timestamp: determine_timestamp(value.timestamp),
             status: value.status().as_str_name().to_string(),
-        }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_reports_without_a_key_type() {
+        let report = ServiceProviderBoostedRewardsBannedRadioIngestReportV1 {
+            report: Some(ServiceProviderBoostedRewardsBannedRadioReqV1::default()),
+            ..Default::default()
+        };
+
+        assert!(ServiceProviderBannedRadioIngest::try_from(report).is_err());
     }
 }
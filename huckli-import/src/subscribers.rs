@@ -1,11 +1,15 @@
+use anyhow::Context;
 use chrono::{DateTime, Utc};
 use helium_proto::services::poc_mobile::{
-    SubscriberMappingActivityIngestReportV1, VerifiedSubscriberMappingActivityReportV1,
+    SubscriberLocationIngestReportV1, SubscriberMappingActivityIngestReportV1,
+    SubscriberVerifiedMappingEventIngestReportV1, VerifiedSubscriberLocationIngestReportV1,
+    VerifiedSubscriberMappingActivityReportV1,
+    VerifiedSubscriberVerifiedMappingEventIngestReportV1,
 };
 use huckli_import_derive::Import;
 use uuid::Uuid;
 
-use crate::determine_timestamp;
+use crate::{PublicKeyBinary, determine_timestamp, non_empty};
 
 #[derive(Debug, Import)]
 #[import(s3decode(
@@ -19,24 +23,24 @@ pub struct SubscriberMappingActivityIngest {
     discovery_reward_shares: u64,
     #[import(sql = "bigint")]
     verification_reward_shares: u64,
-    #[import(sql = "timestamptz")]
     timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     received_timestamp: DateTime<Utc>,
-    reward_override_entity_key: String,
+    reward_override_entity_key: Option<String>,
 }
 
-impl From<SubscriberMappingActivityIngestReportV1> for SubscriberMappingActivityIngest {
-    fn from(value: SubscriberMappingActivityIngestReportV1) -> Self {
-        let report = value.report.unwrap();
-        Self {
-            subscriber_id: Uuid::from_slice(&report.subscriber_id).unwrap().to_string(),
+impl TryFrom<SubscriberMappingActivityIngestReportV1> for SubscriberMappingActivityIngest {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SubscriberMappingActivityIngestReportV1) -> anyhow::Result<Self> {
+        let report = value.report.context("missing report")?;
+        Ok(Self {
+            subscriber_id: Uuid::from_slice(&report.subscriber_id)?.to_string(),
             discovery_reward_shares: report.discovery_reward_shares,
             verification_reward_shares: report.verification_reward_shares,
             timestamp: determine_timestamp(report.timestamp),
             received_timestamp: determine_timestamp(value.received_timestamp),
-            reward_override_entity_key: report.reward_override_entity_key,
-        }
+            reward_override_entity_key: non_empty(&report.reward_override_entity_key),
+        })
     }
 }
 
@@ -52,30 +56,187 @@ pub struct VerifiedSubscriberMappingActivity {
     discovery_reward_shares: u64,
     #[import(sql = "bigint")]
     verification_reward_shares: u64,
-    #[import(sql = "timestamptz")]
     timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     received_timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     verification_timestamp: DateTime<Utc>,
-    reward_override_entity_key: String,
+    reward_override_entity_key: Option<String>,
     status: String,
 }
 
-impl From<VerifiedSubscriberMappingActivityReportV1> for VerifiedSubscriberMappingActivity {
-    fn from(value: VerifiedSubscriberMappingActivityReportV1) -> Self {
-        let report = value.report.as_ref().unwrap().report.as_ref().unwrap();
-        let ingest = value.report.as_ref().unwrap();
+impl TryFrom<VerifiedSubscriberMappingActivityReportV1> for VerifiedSubscriberMappingActivity {
+    type Error = anyhow::Error;
+
+    fn try_from(value: VerifiedSubscriberMappingActivityReportV1) -> anyhow::Result<Self> {
+        let ingest = value.report.as_ref().context("missing ingest report")?;
+        let report = ingest.report.as_ref().context("missing report")?;
 
-        Self {
-            subscriber_id: Uuid::from_slice(&report.subscriber_id).unwrap().to_string(),
+        Ok(Self {
+            subscriber_id: Uuid::from_slice(&report.subscriber_id)?.to_string(),
             discovery_reward_shares: report.discovery_reward_shares,
             verification_reward_shares: report.verification_reward_shares,
             timestamp: determine_timestamp(report.timestamp),
             received_timestamp: determine_timestamp(ingest.received_timestamp),
             verification_timestamp: determine_timestamp(value.timestamp),
-            reward_override_entity_key: report.reward_override_entity_key.clone(),
+            reward_override_entity_key: non_empty(&report.reward_override_entity_key),
+            status: value.status().as_str_name().to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = SubscriberLocationIngestReportV1,
+    bucket = "helium-mainnet-mobile-ingest",
+    prefix = "subscriber_location_ingest_report",
+))]
+pub struct SubscriberLocationIngest {
+    subscriber_id: String,
+    timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    carrier_pub_key: String,
+}
+
+impl TryFrom<SubscriberLocationIngestReportV1> for SubscriberLocationIngest {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SubscriberLocationIngestReportV1) -> anyhow::Result<Self> {
+        let report = value.report.context("missing report")?;
+        Ok(Self {
+            subscriber_id: Uuid::from_slice(&report.subscriber_id)?.to_string(),
+            timestamp: determine_timestamp(report.timestamp),
+            received_timestamp: determine_timestamp(value.received_timestamp),
+            carrier_pub_key: PublicKeyBinary::from(report.carrier_pub_key).to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = VerifiedSubscriberLocationIngestReportV1,
+    bucket = "helium-mainnet-mobile-verified",
+    prefix = "verified_subscriber_location_ingest_report",
+))]
+pub struct VerifiedSubscriberLocation {
+    subscriber_id: String,
+    timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    verification_timestamp: DateTime<Utc>,
+    carrier_pub_key: String,
+    status: String,
+}
+
+impl TryFrom<VerifiedSubscriberLocationIngestReportV1> for VerifiedSubscriberLocation {
+    type Error = anyhow::Error;
+
+    fn try_from(value: VerifiedSubscriberLocationIngestReportV1) -> anyhow::Result<Self> {
+        let ingest = value.report.as_ref().context("missing ingest report")?;
+        let report = ingest.report.as_ref().context("missing report")?;
+
+        Ok(Self {
+            subscriber_id: Uuid::from_slice(&report.subscriber_id)?.to_string(),
+            timestamp: determine_timestamp(report.timestamp),
+            received_timestamp: determine_timestamp(ingest.received_timestamp),
+            verification_timestamp: determine_timestamp(value.timestamp),
+            carrier_pub_key: PublicKeyBinary::from(report.carrier_pub_key.clone()).to_string(),
             status: value.status().as_str_name().to_string(),
-        }
+        })
+    }
+}
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = SubscriberVerifiedMappingEventIngestReportV1,
+    bucket = "helium-mainnet-mobile-ingest",
+    prefix = "subscriber_verified_mapping_ingest_report",
+))]
+pub struct SubscriberVerifiedMappingEventIngest {
+    subscriber_id: String,
+    total_reward_points: u32,
+    timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    carrier_mapping_key: String,
+}
+
+impl TryFrom<SubscriberVerifiedMappingEventIngestReportV1>
+    for SubscriberVerifiedMappingEventIngest
+{
+    type Error = anyhow::Error;
+
+    fn try_from(value: SubscriberVerifiedMappingEventIngestReportV1) -> anyhow::Result<Self> {
+        let report = value.report.context("missing report")?;
+        Ok(Self {
+            subscriber_id: Uuid::from_slice(&report.subscriber_id)?.to_string(),
+            total_reward_points: report.total_reward_points,
+            timestamp: determine_timestamp(report.timestamp),
+            received_timestamp: determine_timestamp(value.received_timestamp),
+            carrier_mapping_key: PublicKeyBinary::from(report.carrier_mapping_key).to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = VerifiedSubscriberVerifiedMappingEventIngestReportV1,
+    bucket = "helium-mainnet-mobile-verified",
+    prefix = "verified_subscriber_verified_mapping_ingest_report",
+))]
+pub struct VerifiedSubscriberVerifiedMappingEvent {
+    subscriber_id: String,
+    total_reward_points: u32,
+    timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    verification_timestamp: DateTime<Utc>,
+    carrier_mapping_key: String,
+    status: String,
+}
+
+impl TryFrom<VerifiedSubscriberVerifiedMappingEventIngestReportV1>
+    for VerifiedSubscriberVerifiedMappingEvent
+{
+    type Error = anyhow::Error;
+
+    fn try_from(
+        value: VerifiedSubscriberVerifiedMappingEventIngestReportV1,
+    ) -> anyhow::Result<Self> {
+        let ingest = value.report.as_ref().context("missing ingest report")?;
+        let report = ingest.report.as_ref().context("missing report")?;
+
+        Ok(Self {
+            subscriber_id: Uuid::from_slice(&report.subscriber_id)?.to_string(),
+            total_reward_points: report.total_reward_points,
+            timestamp: determine_timestamp(report.timestamp),
+            received_timestamp: determine_timestamp(ingest.received_timestamp),
+            verification_timestamp: determine_timestamp(value.timestamp),
+            carrier_mapping_key: PublicKeyBinary::from(report.carrier_mapping_key.clone())
+                .to_string(),
+            status: value.status().as_str_name().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location_report(subscriber_id: Vec<u8>) -> SubscriberLocationIngestReportV1 {
+        let mut value = SubscriberLocationIngestReportV1 {
+            report: Some(Default::default()),
+            ..Default::default()
+        };
+        value.report.as_mut().unwrap().subscriber_id = subscriber_id;
+        value
+    }
+
+    #[test]
+    fn rejects_subscriber_ids_that_are_not_uuids() {
+        let report = SubscriberLocationIngest::try_from(location_report(vec![1; 16])).unwrap();
+        assert_eq!(report.subscriber_id, "01010101-0101-0101-0101-010101010101");
+
+        assert!(SubscriberLocationIngest::try_from(location_report(vec![1; 3])).is_err());
+        assert!(SubscriberLocationIngest::try_from(location_report(vec![])).is_err());
+        assert!(
+            SubscriberLocationIngest::try_from(SubscriberLocationIngestReportV1::default())
+                .is_err()
+        );
     }
 }
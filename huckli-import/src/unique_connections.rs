@@ -1,5 +1,7 @@
 use chrono::{DateTime, Utc};
-use helium_proto::services::poc_mobile::VerifiedUniqueConnectionsIngestReportV1;
+use helium_proto::services::poc_mobile::{
+    UniqueConnectionsIngestReportV1, VerifiedUniqueConnectionsIngestReportV1,
+};
 use huckli_import_derive::Import;
 
 use crate::{PublicKeyBinary, determine_timestamp};
@@ -12,17 +14,11 @@ use crate::{PublicKeyBinary, determine_timestamp};
 ))]
 pub struct VerifiedUniqueConnections {
     hotspot_key: String,
-    #[import(sql = "timestamptz")]
     start_timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_timestamp: DateTime<Utc>,
-    #[import(sql = "uint64")]
     unique_connections: u64,
-    #[import(sql = "timestamptz")]
     sent_timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     received_timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     verified_timestamp: DateTime<Utc>,
     status: String,
 }
@@ -44,3 +40,37 @@ impl From<VerifiedUniqueConnectionsIngestReportV1> for VerifiedUniqueConnections
         }
     }
 }
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = UniqueConnectionsIngestReportV1,
+    bucket = "helium-mainnet-mobile-ingest",
+    prefix = "unique_connections_report",
+))]
+pub struct UniqueConnectionsIngest {
+    hotspot_key: String,
+    start_timestamp: DateTime<Utc>,
+    end_timestamp: DateTime<Utc>,
+    unique_connections: u64,
+    sent_timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    carrier_key: String,
+    signed: bool,
+}
+
+impl From<UniqueConnectionsIngestReportV1> for UniqueConnectionsIngest {
+    fn from(value: UniqueConnectionsIngestReportV1) -> Self {
+        let req = value.report.as_ref().unwrap();
+
+        Self {
+            hotspot_key: PublicKeyBinary::from(req.pubkey.clone()).to_string(),
+            start_timestamp: determine_timestamp(req.start_timestamp),
+            end_timestamp: determine_timestamp(req.end_timestamp),
+            unique_connections: req.unique_connections,
+            sent_timestamp: determine_timestamp(req.timestamp),
+            received_timestamp: determine_timestamp(value.received_timestamp),
+            carrier_key: PublicKeyBinary::from(req.carrier_key.clone()).to_string(),
+            signed: !req.signature.is_empty(),
+        }
+    }
+}
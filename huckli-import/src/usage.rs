@@ -1,5 +1,7 @@
 use chrono::{DateTime, Utc};
-use helium_proto::services::poc_mobile::RadioUsageStatsIngestReportV1;
+use helium_proto::services::poc_mobile::{
+    HexUsageStatsIngestReportV1, RadioUsageStatsIngestReportV1,
+};
 use huckli_import_derive::Import;
 
 use crate::{PublicKeyBinary, determine_timestamp};
@@ -12,25 +14,15 @@ use crate::{PublicKeyBinary, determine_timestamp};
 ))]
 pub struct RadioUsageStats {
     hotspot_key: String,
-    #[import(sql = "timestamptz")]
     start_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_period: DateTime<Utc>,
-    #[import(sql = "uint64")]
     service_provider_user_count: u64,
-    #[import(sql = "uint64")]
     disco_mapping_user_count: u64,
-    #[import(sql = "uint64")]
     offload_user_count: u64,
-    #[import(sql = "uint64")]
     service_provider_transfer_bytes: u64,
-    #[import(sql = "uint64")]
     offload_transfer_bytes: u64,
-    #[import(sql = "timestamptz")]
     timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     received_timestamp: DateTime<Utc>,
-    #[import(sql = "json")]
     carrier_transfer: serde_json::Value,
 }
 
@@ -77,3 +69,43 @@ impl From<RadioUsageStatsIngestReportV1> for RadioUsageStats {
         }
     }
 }
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = HexUsageStatsIngestReportV1,
+    bucket = "helium-mainnet-mobile-ingest",
+    prefix = "hex_usage_stats_ingest_report",
+))]
+pub struct HexUsageStats {
+    hex: u64,
+    start_period: DateTime<Utc>,
+    end_period: DateTime<Utc>,
+    service_provider_user_count: u64,
+    disco_mapping_user_count: u64,
+    offload_user_count: u64,
+    service_provider_transfer_bytes: u64,
+    offload_transfer_bytes: u64,
+    timestamp: DateTime<Utc>,
+    received_timestamp: DateTime<Utc>,
+    carrier_mapping_key: String,
+}
+
+impl From<HexUsageStatsIngestReportV1> for HexUsageStats {
+    fn from(value: HexUsageStatsIngestReportV1) -> Self {
+        let req = value.report.as_ref().unwrap();
+
+        Self {
+            hex: req.hex,
+            start_period: determine_timestamp(req.epoch_start_timestamp),
+            end_period: determine_timestamp(req.epoch_end_timestamp),
+            service_provider_user_count: req.service_provider_user_count,
+            disco_mapping_user_count: req.disco_mapping_user_count,
+            offload_user_count: req.offload_user_count,
+            service_provider_transfer_bytes: req.service_provider_transfer_bytes,
+            offload_transfer_bytes: req.offload_transfer_bytes,
+            timestamp: determine_timestamp(req.timestamp),
+            received_timestamp: determine_timestamp(value.received_timestamp),
+            carrier_mapping_key: PublicKeyBinary::from(req.carrier_mapping_key.clone()).to_string(),
+        }
+    }
+}
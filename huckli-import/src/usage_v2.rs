@@ -12,28 +12,17 @@ use crate::{PublicKeyBinary, determine_timestamp};
 ))]
 pub struct RadioUsageStatsV2 {
     hotspot_key: String,
-    #[import(sql = "timestamptz")]
     start_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     end_period: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     received_timestamp: DateTime<Utc>,
     carrier_pubkey: String,
-    #[import(sql = "uint64")]
     user_count_total: u64,
-    #[import(sql = "uint64")]
     rewarded_bytes_transferred_total: u64,
-    #[import(sql = "uint64")]
     unrewarded_bytes_transferred_total: u64,
-    #[import(sql = "uint64")]
     sampling_user_count_total: u64,
-    #[import(sql = "uint64")]
     sampling_bytes_transferred_total: u64,
-    #[import(sql = "json")]
     carrier_transfer_info: serde_json::Value,
-    #[import(sql = "json")]
     sampling_carrier_transfer_info: serde_json::Value,
 }
 
@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use helium_proto::services::poc_mobile::VerifiedSpeedtest;
+use helium_proto::services::poc_mobile::{SpeedtestIngestReportV1, VerifiedSpeedtest};
 use huckli_import_derive::Import;
 
 use crate::{PublicKeyBinary, determine_timestamp};
@@ -8,22 +8,17 @@ use crate::{PublicKeyBinary, determine_timestamp};
 #[import(s3decode(
     proto = VerifiedSpeedtest,
     bucket = "helium-mainnet-mobile-verified",
-    prefix = "verified_speedtest"
+    prefix = "verified_speedtest",
+    name = "verified-speedtest"
 ))]
 pub struct VerifiedSpeedtestReport {
     hotspot_key: String,
     serial: String,
-    #[import(sql = "timestamptz")]
     timestamp: DateTime<Utc>,
-    #[import(sql = "uint64")]
     upload_speed: u64,
-    #[import(sql = "uint64")]
     download_speed: u64,
-    #[import(sql = "uint32")]
     latency: u32,
-    #[import(sql = "timestamptz")]
     received_timestamp: DateTime<Utc>,
-    #[import(sql = "timestamptz")]
     verified_timestamp: DateTime<Utc>,
     result: String,
 }
@@ -46,3 +41,36 @@ impl From<VerifiedSpeedtest> for VerifiedSpeedtestReport {
         }
     }
 }
+
+#[derive(Debug, Import)]
+#[import(s3decode(
+    proto = SpeedtestIngestReportV1,
+    bucket = "helium-mainnet-mobile-ingest",
+    prefix = "speedtest_report",
+    name = "speedtest-ingest"
+))]
+pub struct SpeedtestIngestReport {
+    hotspot_key: String,
+    serial: String,
+    timestamp: DateTime<Utc>,
+    upload_speed: u64,
+    download_speed: u64,
+    latency: u32,
+    received_timestamp: DateTime<Utc>,
+}
+
+impl From<SpeedtestIngestReportV1> for SpeedtestIngestReport {
+    fn from(value: SpeedtestIngestReportV1) -> Self {
+        let req = value.report.as_ref().unwrap();
+
+        Self {
+            hotspot_key: PublicKeyBinary::from(req.pub_key.clone()).to_string(),
+            serial: req.serial.clone(),
+            timestamp: determine_timestamp(req.timestamp),
+            upload_speed: req.upload_speed,
+            download_speed: req.download_speed,
+            latency: req.latency,
+            received_timestamp: determine_timestamp(value.received_timestamp),
+        }
+    }
+}
@@ -75,6 +75,14 @@ pub struct S3 {
 }
 
 impl S3 {
+    pub fn bucket(&self) -> Option<&str> {
+        self.bucket.as_deref()
+    }
+
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
+    }
+
     pub async fn list_all<A, B>(
         &self,
         bucket: &str,